@@ -3,3 +3,55 @@
 pub(crate) const P256VERIFY_ADDRESS: u64 = 0x14;
 
 pub(crate) const ED25519VERIFY_ADDRESS: u64 = 0x15;
+
+pub(crate) const ED25519PHVERIFY_ADDRESS: u64 = 0x16;
+
+pub(crate) const ED25519CTXVERIFY_ADDRESS: u64 = 0x17;
+
+pub(crate) const ED25519BATCHVERIFY_ADDRESS: u64 = 0x18;
+
+pub(crate) const ED25519BATCHBITMAP_ADDRESS: u64 = 0x19;
+
+pub(crate) const ED448VERIFY_ADDRESS: u64 = 0x1a;
+
+pub(crate) const SCHNORRVERIFY_ADDRESS: u64 = 0x1b;
+
+pub(crate) const SCHNORRBATCHVERIFY_ADDRESS: u64 = 0x1c;
+
+pub(crate) const SR25519VERIFY_ADDRESS: u64 = 0x1d;
+
+pub(crate) const RISTRETTO255_ADDRESS: u64 = 0x1e;
+
+pub(crate) const EDWARDS25519_ADDRESS: u64 = 0x1f;
+
+pub(crate) const EDWARDS25519_MSM_ADDRESS: u64 = 0x20;
+
+pub(crate) const RISTRETTO255_MSM_ADDRESS: u64 = 0x21;
+
+pub(crate) const SHA512_ADDRESS: u64 = 0x22;
+
+pub(crate) const SHA384_ADDRESS: u64 = 0x23;
+
+pub(crate) const SHA512_256_ADDRESS: u64 = 0x24;
+
+pub(crate) const BLAKE2B_ADDRESS: u64 = 0x25;
+
+pub(crate) const ED25519PUBKEYVALIDATE_ADDRESS: u64 = 0x26;
+
+pub(crate) const ED25519TOX25519_ADDRESS: u64 = 0x27;
+
+pub(crate) const HASH_TO_CURVE_ADDRESS: u64 = 0x28;
+
+pub(crate) const ECVRFVERIFY_ADDRESS: u64 = 0x29;
+
+pub(crate) const BULLETPROOFSVERIFY_ADDRESS: u64 = 0x2a;
+
+pub(crate) const ED25519VERIFY_EIP665_ADDRESS: u64 = 0x2b;
+
+pub(crate) const ED25519KECCAKVERIFY_ADDRESS: u64 = 0x2c;
+
+pub(crate) const ED25519THRESHOLDVERIFY_ADDRESS: u64 = 0x2d;
+
+pub(crate) const ED25519MERKLEVERIFY_ADDRESS: u64 = 0x2e;
+
+pub(crate) const ED25519HALFAGGVERIFY_ADDRESS: u64 = 0x2f;
@@ -0,0 +1,554 @@
+//! # BIP-340 Schnorr Precompile
+//!
+//! This module implements precompiles for [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki)
+//! Schnorr signatures over secp256k1, as used by Bitcoin Taproot and Nostr.
+//!
+//! The [`SCHNORRVERIFY`] const represents the implementation of the single signature precompile,
+//! and [`SCHNORRBATCHVERIFY`] checks many signatures in one call, both with the addresses that they
+//! are currently deployed at.
+
+use crate::addresses::{SCHNORRBATCHVERIFY_ADDRESS, SCHNORRVERIFY_ADDRESS};
+use k256::{
+    elliptic_curve::{
+        group::Group,
+        ops::{LinearCombinationExt, MulByGenerator, Reduce},
+        point::DecompressPoint,
+        subtle::Choice,
+        PrimeField,
+    },
+    schnorr::{Signature, VerifyingKey},
+    AffinePoint, FieldBytes, ProjectivePoint, Scalar, U256,
+};
+use revm::{
+    precompile::{calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{
+        Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, B256,
+    },
+};
+use sha2::{digest::Output, Digest, Sha256};
+
+/// Base gas fee for schnorrverify operation.
+const SCHNORRVERIFY_BASE: u64 = 3_450;
+
+/// Gas fee charged for every 32-byte word of the signed message, rounded up.
+const SCHNORRVERIFY_PER_WORD: u64 = 12;
+
+/// Length of the x-only public key.
+const PUBLIC_KEY_LEN: usize = 32;
+
+/// Base gas fee for schnorrbatchverify operation, charged once per call for the multiplication of
+/// the generator and the hashing of the coefficients.
+const SCHNORRBATCHVERIFY_BASE: u64 = 1_750;
+
+/// Gas fee charged for every signature of a schnorrbatchverify call, on top of
+/// [`SCHNORRVERIFY_PER_WORD`] for every 32-byte word of its message. Measured against
+/// [`SCHNORRVERIFY`], a signature in a large batch takes about three quarters of the time.
+const SCHNORRBATCHVERIFY_PER_SIGNATURE: u64 = 2_600;
+
+/// Length of the big-endian message length that follows each public key in a batch.
+const SCHNORRBATCHVERIFY_MSG_LEN_LEN: usize = 4;
+
+/// BIP-340 tag of the challenge hash.
+const CHALLENGE_TAG: &[u8] = b"BIP0340/challenge";
+
+/// Tag of the hash that the coefficients of a batch are derived from.
+const BATCH_TAG: &[u8] = b"SCHNORRBATCHVERIFY/coefficients";
+
+/// Length of the random coefficients of the batch equation, which are 128-bit scalars.
+const COEFFICIENT_LEN: usize = 16;
+
+/// Returns the BIP-340 precompiles with their addresses.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [SCHNORRVERIFY, SCHNORRBATCHVERIFY].into_iter()
+}
+
+/// BIP-340 precompile.
+pub const SCHNORRVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SCHNORRVERIFY_ADDRESS),
+    Precompile::Standard(schnorr_verify),
+);
+
+/// BIP-340 batch verification precompile.
+pub const SCHNORRBATCHVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SCHNORRBATCHVERIFY_ADDRESS),
+    Precompile::Standard(schnorr_batch_verify),
+);
+
+/// BIP-340 precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output represents the result of verifying the
+/// Schnorr signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// | x-only public key | message  |  r  |  s  |
+/// | :---------------: | :------: | :-: | :-: |
+/// |        32         | variable | 32  | 32  |
+///
+/// The message is usually a 32-byte hash, but BIP-340 allows messages of any length. Gas is
+/// charged as [`SCHNORRVERIFY_BASE`] plus [`SCHNORRVERIFY_PER_WORD`] for every 32-byte word of the
+/// message, like `ED25519VERIFY`.
+fn schnorr_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let msg_len = input
+        .len()
+        .saturating_sub(PUBLIC_KEY_LEN + Signature::BYTE_SIZE);
+    let gas_used = calc_linear_cost_u32(msg_len, SCHNORRVERIFY_BASE, SCHNORRVERIFY_PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = verify_impl(input).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// BIP-340 batch verification precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output is 1 if every signature in the input is valid, and 0 otherwise.
+///
+/// The input is a non-empty sequence of entries, each encoded as follows:
+///
+/// | x-only public key | message length | message  |  r  |  s  |
+/// | :---------------: | :------------: | :------: | :-: | :-: |
+/// |        32         |  4, big-endian | variable | 32  | 32  |
+///
+/// The signatures are checked together with the randomized batch equation of BIP-340, which holds
+/// exactly when every signature would pass [`SCHNORRVERIFY`], up to a chance of `2^-128` that a
+/// batch with an invalid signature passes. The coefficients are derived from the whole input,
+/// with `T = SHA-256(SHA-256(tag) || SHA-256(tag) || entries)` for the tag
+/// `SCHNORRBATCHVERIFY/coefficients`, and the coefficient of entry `i > 0` is the first 16 bytes
+/// of `SHA-256(T || i)`, with `i` a 4-byte big-endian integer, read as a big-endian integer.
+///
+/// Gas is charged as [`SCHNORRBATCHVERIFY_BASE`], plus [`SCHNORRBATCHVERIFY_PER_SIGNATURE`] and
+/// [`SCHNORRVERIFY_PER_WORD`] for every 32-byte word of the message for each entry. A batch of
+/// three or more signatures costs less than separate [`SCHNORRVERIFY`] calls, and a batch of one
+/// or two costs more. Malformed inputs are charged only the base fee.
+fn schnorr_batch_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let entries = parse_batch(input);
+    let gas_used = entries
+        .as_deref()
+        .map_or(SCHNORRBATCHVERIFY_BASE, |entries| {
+            entries.iter().fold(SCHNORRBATCHVERIFY_BASE, |gas, entry| {
+                gas + calc_linear_cost_u32(
+                    entry.msg.len(),
+                    SCHNORRBATCHVERIFY_PER_SIGNATURE,
+                    SCHNORRVERIFY_PER_WORD,
+                )
+            })
+        });
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = entries.is_some_and(|entries| batch_verify_impl(&entries).is_some());
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Returns `Some(())` if the signature included in the input byte slice is
+/// valid, `None` otherwise.
+fn verify_impl(input: &[u8]) -> Option<()> {
+    if input.len() < PUBLIC_KEY_LEN + Signature::BYTE_SIZE {
+        return None;
+    }
+
+    // x-only public key
+    let (pk, rest) = input.split_at(PUBLIC_KEY_LEN);
+    // message, then r, s: signature
+    let (msg, sig) = rest.split_at(rest.len() - Signature::BYTE_SIZE);
+
+    verify_signature(pk, msg, sig)
+}
+
+/// Returns `Some(())` if `sig` is a valid signature of `msg` by the x-only public key `pk`, `None`
+/// otherwise.
+fn verify_signature(pk: &[u8], msg: &[u8], sig: &[u8]) -> Option<()> {
+    // Can fail if r is not a field element or s is not a non-zero scalar.
+    let signature = Signature::try_from(sig).ok()?;
+    // Can fail if the input is not valid, so we have to propagate the error.
+    let public_key = VerifyingKey::from_bytes(pk).ok()?;
+
+    public_key.verify_raw(msg, &signature).ok()
+}
+
+/// Returns `Some(())` if every signature in the batch is valid, `None` otherwise.
+///
+/// With `a_0 = 1` and `a_i` the random coefficients, the batch is valid if
+/// `sum(a_i * s_i) * G = sum(a_i * R_i + a_i * e_i * P_i)`, where `R_i` and `P_i` are the points
+/// with even y coordinates that the x-only `r` and public key encode.
+fn batch_verify_impl(entries: &[BatchEntry<'_>]) -> Option<()> {
+    let mut transcript = tagged_hash(BATCH_TAG);
+    for entry in entries {
+        transcript.update(entry.pk);
+        transcript.update((entry.msg.len() as u32).to_be_bytes());
+        transcript.update(entry.msg);
+        transcript.update(entry.sig);
+    }
+    let transcript = transcript.finalize();
+
+    let mut terms = Vec::with_capacity(2 * entries.len());
+    let mut s_sum = Scalar::ZERO;
+    for (i, entry) in entries.iter().enumerate() {
+        let (r_bytes, s_bytes) = entry.sig.split_at(32);
+        let p = lift_x(entry.pk)?;
+        let r = lift_x(r_bytes)?;
+        let s = Option::<Scalar>::from(Scalar::from_repr(*FieldBytes::from_slice(s_bytes)))?;
+        // k256 rejects a zero s in single verification, so the batch does too
+        if bool::from(s.is_zero()) {
+            return None;
+        }
+        let e = <Scalar as Reduce<U256>>::reduce_bytes(
+            &tagged_hash(CHALLENGE_TAG)
+                .chain_update(r_bytes)
+                .chain_update(entry.pk)
+                .chain_update(entry.msg)
+                .finalize(),
+        );
+
+        let a = if i == 0 {
+            Scalar::ONE
+        } else {
+            random_coefficient(&transcript, i)
+        };
+        s_sum += a * s;
+        terms.push((r, -a));
+        terms.push((p, -(a * e)));
+    }
+    // the generator has precomputed tables, so its multiple is faster to compute on its own
+    let sum = ProjectivePoint::mul_by_generator(&s_sum) + ProjectivePoint::lincomb_ext(&terms[..]);
+    bool::from(sum.is_identity()).then_some(())
+}
+
+/// Returns the point with an even y coordinate whose x coordinate is the big-endian `x`, or `None`
+/// if `x` is not below the field size or is not the x coordinate of a point.
+fn lift_x(x: &[u8]) -> Option<ProjectivePoint> {
+    let point = AffinePoint::decompress(FieldBytes::from_slice(x), Choice::from(0));
+    Option::<AffinePoint>::from(point).map(ProjectivePoint::from)
+}
+
+/// Returns a SHA-256 hasher that has absorbed the BIP-340 prefix `SHA-256(tag) || SHA-256(tag)`.
+fn tagged_hash(tag: &[u8]) -> Sha256 {
+    let tag_hash = Sha256::digest(tag);
+    Sha256::new().chain_update(tag_hash).chain_update(tag_hash)
+}
+
+/// Derives the coefficient of the entry at `index` in a batch from the hash of the batch, as the
+/// first [`COEFFICIENT_LEN`] bytes of `SHA-256(transcript || index)`, with `index` as a 4-byte
+/// big-endian integer, read as a big-endian scalar.
+fn random_coefficient(transcript: &Output<Sha256>, index: usize) -> Scalar {
+    let hash = Sha256::new()
+        .chain_update(transcript)
+        .chain_update((index as u32).to_be_bytes())
+        .finalize();
+    let mut a = FieldBytes::default();
+    a[32 - COEFFICIENT_LEN..].copy_from_slice(&hash[..COEFFICIENT_LEN]);
+    Scalar::from_repr(a).expect("128-bit integers are below the group order")
+}
+
+/// A single `(public key, message, signature)` entry of a batch.
+struct BatchEntry<'a> {
+    /// x-only public key
+    pk: &'a [u8],
+    /// message
+    msg: &'a [u8],
+    /// r, s: signature
+    sig: &'a [u8],
+}
+
+/// Splits the input of the batch precompile into its entries, returning `None` if it is empty or
+/// malformed.
+fn parse_batch(mut input: &[u8]) -> Option<Vec<BatchEntry<'_>>> {
+    let mut entries = Vec::new();
+    while !input.is_empty() {
+        let (pk, rest) = input.split_at_checked(PUBLIC_KEY_LEN)?;
+        let (msg_len, rest) = rest.split_at_checked(SCHNORRBATCHVERIFY_MSG_LEN_LEN)?;
+        let msg_len = u32::from_be_bytes(msg_len.try_into().unwrap()) as usize;
+        let (msg, rest) = rest.split_at_checked(msg_len)?;
+        let (sig, rest) = rest.split_at_checked(Signature::BYTE_SIZE)?;
+        entries.push(BatchEntry { pk, msg, sig });
+        input = rest;
+    }
+    (!entries.is_empty()).then_some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::hex;
+
+    /// BIP-340 vectors as `(public key, message, signature, valid)`.
+    type Vector = ([u8; 32], &'static [u8], [u8; 64], bool);
+
+    /// The vectors of BIP-340's `test-vectors.csv`.
+    const VECTORS: [Vector; 19] = [
+        // 0
+        (
+            hex!("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"),
+            &hex!("0000000000000000000000000000000000000000000000000000000000000000"),
+            hex!(
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca8215"
+                "25f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0"
+            ),
+            true,
+        ),
+        // 1
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de3341"
+                "8906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a"
+            ),
+            true,
+        ),
+        // 2
+        (
+            hex!("dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8"),
+            &hex!("7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c"),
+            hex!(
+                "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1b"
+                "ab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7"
+            ),
+            true,
+        ),
+        // 3
+        (
+            hex!("25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517"),
+            &hex!("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"),
+            hex!(
+                "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec"
+                "97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3"
+            ),
+            true,
+        ),
+        // 4
+        (
+            hex!("d69c3509bb99e412e68b0fe8544e72837dfa30746d8be2aa65975f29d22dc7b9"),
+            &hex!("4df3c3f68fcc83b27e9d42c90431a72499f17875c81a599b566c9889b9696703"),
+            hex!(
+                "00000000000000000000003b78ce563f89a0ed9414f5aa28ad0d96d6795f9c63"
+                "76afb1548af603b3eb45c9f8207dee1060cb71c04e80f593060b07d28308d7f4"
+            ),
+            true,
+        ),
+        // 5: public key not on curve
+        (
+            hex!("eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769"
+                "69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b"
+            ),
+            false,
+        ),
+        // 6: has_even_y(R) is false
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a1460297556"
+                "3cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2"
+            ),
+            false,
+        ),
+        // 7: negated message
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "1fa62e331edbc21c394792d2ab1100a7b432b013df3f6ff4f99fcb33e0e1515f"
+                "28890b3edb6e7189b630448b515ce4f8622a954cfe545735aaea5134fccdb2bd"
+            ),
+            false,
+        ),
+        // 8: negated s value
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769"
+                "961764b3aa9b2ffcb6ef947b6887a226e8d7c93e00c5ed0c1834ff0d0c2e6da6"
+            ),
+            false,
+        ),
+        // 9: sG - eP is infinite. Test fails in single verification if has_even_y(inf) is defined
+        // as true and x(inf) as 0
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "0000000000000000000000000000000000000000000000000000000000000000"
+                "123dda8328af9c23a94c1feecfd123ba4fb73476f0d594dcb65c6425bd186051"
+            ),
+            false,
+        ),
+        // 10: sG - eP is infinite. Test fails in single verification if has_even_y(inf) is defined
+        // as true and x(inf) as 1
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+                "7615fbaf5ae28864013c099742deadb4dba87f11ac6754f93780d5a1837cf197"
+            ),
+            false,
+        ),
+        // 11: sig[0:32] is not an X coordinate on the curve
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "4a298dacae57395a15d0795ddbfd1dcb564da82b0f269bc70a74f8220429ba1d"
+                "69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b"
+            ),
+            false,
+        ),
+        // 12: sig[0:32] is equal to field size
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"
+                "69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b"
+            ),
+            false,
+        ),
+        // 13: sig[32:64] is equal to curve order
+        (
+            hex!("dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769"
+                "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+            ),
+            false,
+        ),
+        // 14: public key is not a valid X coordinate because it exceeds the field size
+        (
+            hex!("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30"),
+            &hex!("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89"),
+            hex!(
+                "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769"
+                "69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b"
+            ),
+            false,
+        ),
+        // 15
+        (
+            hex!("778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117"),
+            b"",
+            hex!(
+                "71535db165ecd9fbbc046e5ffaea61186bb6ad436732fccc25291a55895464cf"
+                "6069ce26bf03466228f19a3a62db8a649f2d560fac652827d1af0574e427ab63"
+            ),
+            true,
+        ),
+        // 16
+        (
+            hex!("778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117"),
+            &hex!("11"),
+            hex!(
+                "08a20a0afef64124649232e0693c583ab1b9934ae63b4c3511f3ae1134c6a303"
+                "ea3173bfea6683bd101fa5aa5dbc1996fe7cacfc5a577d33ec14564cec2bacbf"
+            ),
+            true,
+        ),
+        // 17
+        (
+            hex!("778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117"),
+            &hex!("0102030405060708090a0b0c0d0e0f1011"),
+            hex!(
+                "5130f39a4059b43bc7cac09a19ece52b5d8699d1a71e3c52da9afdb6b50ac370"
+                "c4a482b77bf960f8681540e25b6771ece1e5a37fd80e5a51897c5566a97ea5a5"
+            ),
+            true,
+        ),
+        // 18
+        (
+            hex!("778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117"),
+            &[0x99; 100],
+            hex!(
+                "403b12b0d8555a344175ea7ec746566303321e5dbfa8be6f091635163eca79a8"
+                "585ed3e3170807e7c03b720fc54c7b23897fcba0e9d0b4a06894cfd249f22367"
+            ),
+            true,
+        ),
+    ];
+
+    /// Encodes the input of [`SCHNORRBATCHVERIFY`] for `(public key, message, signature)` entries.
+    fn batch_input(entries: &[Vector]) -> Bytes {
+        let mut input = Vec::new();
+        for (pk, msg, sig, _) in entries {
+            input.extend_from_slice(pk);
+            input.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+            input.extend_from_slice(msg);
+            input.extend_from_slice(sig);
+        }
+        input.into()
+    }
+
+    /// Returns whether [`SCHNORRBATCHVERIFY`] accepts the entries.
+    fn batch_verifies(entries: &[Vector]) -> bool {
+        schnorr_batch_verify(&batch_input(entries), u64::MAX)
+            .unwrap()
+            .bytes[31]
+            == 1
+    }
+
+    #[test]
+    fn verifies_bip340_vectors() {
+        for (i, (pk, msg, sig, valid)) in VECTORS.into_iter().enumerate() {
+            let input = [pk.as_slice(), msg, &sig].concat().into();
+            let output = schnorr_verify(&input, u64::MAX).unwrap();
+            assert_eq!(output.bytes[31] == 1, valid, "vector {i}");
+        }
+    }
+
+    #[test]
+    fn batch_agrees_with_bip340_vectors() {
+        for (i, vector) in VECTORS.into_iter().enumerate() {
+            assert_eq!(batch_verifies(&[vector]), vector.3, "vector {i}");
+            // an invalid signature fails the batch in any position
+            assert_eq!(
+                batch_verifies(&[VECTORS[0], vector]),
+                vector.3,
+                "vector {i}"
+            );
+            assert_eq!(
+                batch_verifies(&[vector, VECTORS[1]]),
+                vector.3,
+                "vector {i}"
+            );
+        }
+        let valid: Vec<_> = VECTORS.into_iter().filter(|vector| vector.3).collect();
+        assert!(batch_verifies(&valid));
+    }
+
+    #[test]
+    fn batch_rejects_mismatched_signatures() {
+        // two valid signatures whose messages are swapped
+        let (mut first, mut second) = (VECTORS[1], VECTORS[2]);
+        (first.1, second.1) = (second.1, first.1);
+        assert!(!batch_verifies(&[first, second]));
+    }
+
+    #[test]
+    fn batch_costs_less_than_separate_verification() {
+        let entries = &VECTORS[..4];
+        let output = schnorr_batch_verify(&batch_input(entries), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        let gas = SCHNORRBATCHVERIFY_BASE
+            + 4 * (SCHNORRBATCHVERIFY_PER_SIGNATURE + SCHNORRVERIFY_PER_WORD);
+        assert_eq!(output.gas_used, gas);
+
+        // which is less than verifying the signatures separately, as it is from three signatures on
+        let single = SCHNORRVERIFY_BASE + SCHNORRVERIFY_PER_WORD;
+        assert!(gas < 4 * single);
+        let three = SCHNORRBATCHVERIFY_BASE
+            + 3 * (SCHNORRBATCHVERIFY_PER_SIGNATURE + SCHNORRVERIFY_PER_WORD);
+        assert!(three < 3 * single);
+
+        // malformed inputs are charged the base fee
+        let input = batch_input(entries);
+        let output =
+            schnorr_batch_verify(&input[..input.len() - 1].to_vec().into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+        assert_eq!(output.gas_used, SCHNORRBATCHVERIFY_BASE);
+    }
+}
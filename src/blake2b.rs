@@ -0,0 +1,96 @@
+//! # BLAKE2b Precompile
+//!
+//! This module implements a precompile for the full BLAKE2b hash function, which Sui, Cardano and
+//! Zcash hash the messages of their ed25519 signatures with. Unlike the EIP-152 precompile at
+//! 0x09, which only exposes the `F` compression function, it handles padding, keying and
+//! parameters itself.
+//!
+//! The [`BLAKE2B`] const represents the implementation of this precompile, with the address that
+//! it is currently deployed at.
+
+use crate::addresses::BLAKE2B_ADDRESS;
+use blake2b_simd::{Params, BLOCKBYTES, KEYBYTES, OUTBYTES, PERSONALBYTES};
+use revm::{
+    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult},
+};
+
+/// Base gas fee for the BLAKE2b precompile.
+const BLAKE2B_BASE: u64 = 60;
+
+/// Gas fee per 128-byte block, the same as the twelve rounds of the EIP-152 `F` function that
+/// compress it.
+const BLAKE2B_PER_BLOCK: u64 = 12;
+
+/// Returns the BLAKE2b precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [BLAKE2B].into_iter()
+}
+
+/// BLAKE2b precompile.
+pub const BLAKE2B: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(BLAKE2B_ADDRESS),
+    Precompile::Standard(blake2b_run),
+);
+
+/// BLAKE2b precompile logic. It takes the input bytes sent to the precompile and the gas limit,
+/// and returns the BLAKE2b digest of the message with the given parameters.
+///
+/// The input is encoded as follows:
+///
+/// | output length | key length | key | personalization |   message  |
+/// | :-----------: | :--------: | :-: | :-------------: | :--------: |
+/// |       1       |      1     |  k  |        16       | remaining  |
+///
+/// The output length is between 1 and 64 bytes, so BLAKE2b-256 and BLAKE2b-512 are 32 and 64.
+/// The key is at most 64 bytes, and an empty key hashes without one. A personalization of zeros
+/// is the same as none.
+///
+/// Gas is charged for every 128-byte block that is compressed, counting the block that a key
+/// takes up. Malformed inputs are errors.
+fn blake2b_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some((&[out_len, key_len], rest)) = input.split_first_chunk::<2>() else {
+        return Err(PrecompileError::other("blake2b: input is too short").into());
+    };
+    let (out_len, key_len) = (out_len as usize, key_len as usize);
+    if !(1..=OUTBYTES).contains(&out_len) {
+        return Err(PrecompileError::other(format!(
+            "blake2b: output length should be between 1 and {OUTBYTES}, got {out_len}"
+        ))
+        .into());
+    }
+    if key_len > KEYBYTES {
+        return Err(PrecompileError::other(format!(
+            "blake2b: key length should be at most {KEYBYTES}, got {key_len}"
+        ))
+        .into());
+    }
+    if rest.len() < key_len + PERSONALBYTES {
+        return Err(PrecompileError::other("blake2b: input is too short").into());
+    }
+    let (key, rest) = rest.split_at(key_len);
+    let (personal, message) = rest.split_at(PERSONALBYTES);
+
+    let gas_used = BLAKE2B_BASE + BLAKE2B_PER_BLOCK * blocks(key_len, message.len());
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let hash = Params::new()
+        .hash_length(out_len)
+        .key(key)
+        .personal(personal)
+        .hash(message);
+    Ok(PrecompileOutput::new(
+        gas_used,
+        hash.as_bytes().to_vec().into(),
+    ))
+}
+
+/// Returns the number of blocks compressed to hash a message of `message_len` bytes with a key of
+/// `key_len` bytes. A key is padded to a block of its own, and the last block is compressed even
+/// when there is no data left for it.
+fn blocks(key_len: usize, message_len: usize) -> u64 {
+    let key_blocks = usize::from(key_len > 0);
+    (key_blocks + message_len.div_ceil(BLOCKBYTES)).max(1) as u64
+}
@@ -0,0 +1,242 @@
+//! # Bulletproofs Precompile
+//!
+//! This module implements a precompile for verifying Bulletproofs range proofs over ristretto255,
+//! which show that Pedersen-committed values, such as confidential token amounts, fit in 64 bits.
+//!
+//! The [`BULLETPROOFSVERIFY`] const represents the implementation of this precompile, with the
+//! address that it is currently deployed at. Proofs are the ones produced by the dalek
+//! `bulletproofs` crate with the default [`PedersenGens`], either for a single commitment or
+//! aggregated over several.
+
+use crate::addresses::BULLETPROOFSVERIFY_ADDRESS;
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek::ristretto::CompressedRistretto;
+use merlin::Transcript;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use revm::{
+    precompile::{calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{
+        Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, B256,
+    },
+};
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+/// Base gas fee for bulletproofsverify operation.
+const BULLETPROOFSVERIFY_BASE: u64 = 40_000;
+
+/// Gas fee charged for every aggregated commitment, which adds 64 bits to the proven range.
+const BULLETPROOFSVERIFY_PER_COMMITMENT: u64 = 45_000;
+
+/// Gas fee charged for every 32-byte word of the proof.
+const BULLETPROOFSVERIFY_PER_WORD: u64 = 100;
+
+/// Number of bits in the range that every committed value is proven to lie in.
+const RANGE_BITS: usize = 64;
+
+/// Maximum number of commitments that a proof can aggregate.
+const MAX_COMMITMENTS: usize = 16;
+
+/// Labels that the prover's transcript can be created with, selected by their index. merlin only
+/// takes `'static` labels, so they cannot come from the input.
+const TRANSCRIPT_LABELS: [&[u8]; 2] = [b"bulletproofsverify", b"RangeProof"];
+
+/// Generators shared by all proofs, which are expensive to derive.
+static BULLETPROOF_GENS: OnceLock<BulletproofGens> = OnceLock::new();
+
+/// Returns the Bulletproofs precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [BULLETPROOFSVERIFY].into_iter()
+}
+
+/// Bulletproofs range proof verification precompile.
+pub const BULLETPROOFSVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(BULLETPROOFSVERIFY_ADDRESS),
+    Precompile::Standard(bulletproofs_verify),
+);
+
+/// Bulletproofs precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit. The output represents the result of verifying the range proof of the input.
+///
+/// The input is encoded as follows:
+///
+/// | label | commitment count |  commitments  |   proof  |
+/// | :---: | :--------------: | :-----------: | :------: |
+/// |   1   |         1        | 32 × count    | variable |
+///
+/// The label selects the one the prover's merlin transcript was created with:
+///
+/// | selector |         label          |
+/// | :------: | :--------------------: |
+/// |   0x00   | `"bulletproofsverify"` |
+/// |   0x01   | `"RangeProof"`         |
+///
+/// Other selectors make the input malformed. The commitments are
+/// compressed ristretto255 points, and there must be a power of two of them, at most
+/// [`MAX_COMMITMENTS`]. The proof is serialized with `RangeProof::to_bytes` and shows that every
+/// committed value is below `2^64`.
+///
+/// Gas is charged as [`BULLETPROOFSVERIFY_BASE`], plus [`BULLETPROOFSVERIFY_PER_COMMITMENT`] for
+/// every commitment and [`BULLETPROOFSVERIFY_PER_WORD`] for every 32-byte word of the proof.
+fn bulletproofs_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some((label, commitments, proof)) = parse_input(input) else {
+        // malformed inputs are charged the base fee and fail verification
+        if BULLETPROOFSVERIFY_BASE > gas_limit {
+            return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+        }
+        let out = PrecompileOutput::new(BULLETPROOFSVERIFY_BASE, B256::ZERO.into());
+        return Ok(out);
+    };
+    let gas_used = calc_linear_cost_u32(
+        proof.len(),
+        BULLETPROOFSVERIFY_BASE,
+        BULLETPROOFSVERIFY_PER_WORD,
+    ) + BULLETPROOFSVERIFY_PER_COMMITMENT * commitments.len() as u64;
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = verify_impl(input, label, &commitments, proof).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Splits the input into the transcript label, the commitments and the proof, or returns `None`
+/// if it is malformed.
+fn parse_input(input: &[u8]) -> Option<(&'static [u8], Vec<CompressedRistretto>, &[u8])> {
+    let (&label, rest) = input.split_first()?;
+    let label = TRANSCRIPT_LABELS.get(label as usize)?;
+    let (&count, rest) = rest.split_first()?;
+    let count = count as usize;
+    if !count.is_power_of_two() || count > MAX_COMMITMENTS {
+        return None;
+    }
+    let (commitments, proof) = rest.split_at_checked(count * 32)?;
+    let commitments = commitments
+        .chunks_exact(32)
+        .map(|commitment| CompressedRistretto(commitment.try_into().unwrap()))
+        .collect();
+    Some((label, commitments, proof))
+}
+
+/// Returns `Some(())` if `proof` is a valid range proof for `commitments` with a transcript
+/// labelled `label`, `None` otherwise.
+fn verify_impl(
+    input: &[u8],
+    label: &'static [u8],
+    commitments: &[CompressedRistretto],
+    proof: &[u8],
+) -> Option<()> {
+    let proof = RangeProof::from_bytes(proof).ok()?;
+    let bp_gens =
+        BULLETPROOF_GENS.get_or_init(|| BulletproofGens::new(RANGE_BITS, MAX_COMMITMENTS));
+    let mut transcript = Transcript::new(label);
+
+    // the verifier batches its checks with a random scalar, which has to be the same on every
+    // node, so it is derived from the whole input like a Fiat-Shamir challenge
+    let mut rng = ChaCha20Rng::from_seed(Sha256::digest(input).into());
+    proof
+        .verify_multiple_with_rng(
+            bp_gens,
+            &PedersenGens::default(),
+            &mut transcript,
+            commitments,
+            RANGE_BITS,
+            &mut rng,
+        )
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::Scalar;
+
+    /// Proves that `values` fit in 64 bits with the transcript label
+    /// `TRANSCRIPT_LABELS[label]`, and encodes the input of [`BULLETPROOFSVERIFY`] for the proof.
+    fn prove(label: u8, values: &[u64]) -> Bytes {
+        let mut rng = ChaCha20Rng::from_seed([7; 32]);
+        let blindings: Vec<_> = (1..=values.len() as u64).map(Scalar::from).collect();
+        let bp_gens =
+            BULLETPROOF_GENS.get_or_init(|| BulletproofGens::new(RANGE_BITS, MAX_COMMITMENTS));
+        let mut transcript = Transcript::new(TRANSCRIPT_LABELS[label as usize]);
+        let (proof, commitments) = RangeProof::prove_multiple_with_rng(
+            bp_gens,
+            &PedersenGens::default(),
+            &mut transcript,
+            values,
+            &blindings,
+            RANGE_BITS,
+            &mut rng,
+        )
+        .unwrap();
+
+        let mut input = vec![label, commitments.len() as u8];
+        for commitment in &commitments {
+            input.extend_from_slice(commitment.as_bytes());
+        }
+        input.extend_from_slice(&proof.to_bytes());
+        input.into()
+    }
+
+    #[test]
+    fn verifies_single_proof() {
+        let mut rng = ChaCha20Rng::from_seed([8; 32]);
+        let bp_gens =
+            BULLETPROOF_GENS.get_or_init(|| BulletproofGens::new(RANGE_BITS, MAX_COMMITMENTS));
+        let mut transcript = Transcript::new(TRANSCRIPT_LABELS[0]);
+        let (proof, commitment) = RangeProof::prove_single_with_rng(
+            bp_gens,
+            &PedersenGens::default(),
+            &mut transcript,
+            u64::MAX,
+            &Scalar::from(42u64),
+            RANGE_BITS,
+            &mut rng,
+        )
+        .unwrap();
+        let mut input = vec![0, 1];
+        input.extend_from_slice(commitment.as_bytes());
+        input.extend_from_slice(&proof.to_bytes());
+
+        let output = bulletproofs_verify(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+    }
+
+    #[test]
+    fn verifies_aggregated_proof() {
+        let input = prove(1, &[0, 1, 1 << 32, u64::MAX]);
+        let output = bulletproofs_verify(&input, u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        let proof_words = (input.len() as u64 - 2 - 4 * 32).div_ceil(32);
+        assert_eq!(
+            output.gas_used,
+            BULLETPROOFSVERIFY_BASE
+                + 4 * BULLETPROOFSVERIFY_PER_COMMITMENT
+                + proof_words * BULLETPROOFSVERIFY_PER_WORD
+        );
+    }
+
+    #[test]
+    fn rejects_proof_for_other_label() {
+        let mut input = prove(0, &[5, 6]).to_vec();
+        input[0] = 1;
+        let output = bulletproofs_verify(&input.clone().into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+
+        // a selector without a label makes the input malformed
+        input[0] = TRANSCRIPT_LABELS.len() as u8;
+        let output = bulletproofs_verify(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+        assert_eq!(output.gas_used, BULLETPROOFSVERIFY_BASE);
+    }
+
+    #[test]
+    fn rejects_proof_for_other_commitment() {
+        let mut input = prove(0, &[5, 6]).to_vec();
+        // swap the two commitments
+        let (first, second) = input[2..66].split_at_mut(32);
+        first.swap_with_slice(second);
+        let output = bulletproofs_verify(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+    }
+}
@@ -0,0 +1,326 @@
+//! # ECVRF Precompile
+//!
+//! This module implements a precompile for verifying [RFC 9381](https://www.rfc-editor.org/rfc/rfc9381)
+//! verifiable random function proofs over edwards25519, as produced by Algorand- and
+//! Cardano-style VRF keys.
+//!
+//! The [`ECVRFVERIFY`] const represents the implementation of this precompile, with the address
+//! that it is currently deployed at. It supports the `ECVRF-EDWARDS25519-SHA512-TAI` and
+//! `ECVRF-EDWARDS25519-SHA512-ELL2` suites.
+
+use crate::{
+    addresses::ECVRFVERIFY_ADDRESS,
+    ed25519::{decompress_canonical, strict_public_key},
+    hash_to_curve::encode_to_edwards25519,
+    utils::GasMeter,
+};
+use curve25519_dalek::{traits::VartimeMultiscalarMul, EdwardsPoint, Scalar};
+use revm::{
+    precompile::{calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileErrors, PrecompileOutput, PrecompileResult},
+};
+use sha2::{Digest, Sha512};
+
+/// Suite string of `ECVRF-EDWARDS25519-SHA512-TAI`.
+const ECVRF_EDWARDS25519_SHA512_TAI: u8 = 0x03;
+
+/// Suite string of `ECVRF-EDWARDS25519-SHA512-ELL2`.
+const ECVRF_EDWARDS25519_SHA512_ELL2: u8 = 0x04;
+
+/// Base gas fee for ecvrfverify operation.
+const ECVRFVERIFY_BASE: u64 = 7_000;
+
+/// Gas fee charged for every 32-byte word of alpha, rounded up, each time it is hashed.
+const ECVRFVERIFY_PER_WORD: u64 = 12;
+
+/// Gas fee for every attempt after the first at encoding alpha to the curve with the TAI suite.
+const ECVRF_TAI_PER_ATTEMPT: u64 = 250;
+
+/// Gas fee for encoding alpha to the curve with the ELL2 suite.
+const ECVRF_ELL2_ENCODE: u64 = 2_500;
+
+/// Length of the fixed-size prefix of the input: the suite string, the public key and the proof.
+const ECVRFVERIFY_PREFIX_LEN: usize = 113;
+
+/// Length of the challenge in a proof, `cLen` in RFC 9381.
+const ECVRF_CHALLENGE_LEN: usize = 16;
+
+/// The RFC 9380 suite whose `encode_to_curve` the ELL2 suite uses.
+const ELL2_H2C_SUITE_ID: &[u8] = b"edwards25519_XMD:SHA-512_ELL2_NU_";
+
+/// Returns the ECVRF precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [ECVRFVERIFY].into_iter()
+}
+
+/// ECVRF proof verification precompile.
+pub const ECVRFVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ECVRFVERIFY_ADDRESS),
+    Precompile::Standard(ecvrf_verify),
+);
+
+/// ECVRF precompile logic. It takes the input bytes sent to the precompile and the gas limit. The
+/// output is the 64-byte VRF output `beta` if the proof is valid, and empty otherwise.
+///
+/// The input is encoded as follows:
+///
+/// | suite | public key | proof: gamma | c  |  s  |   alpha  |
+/// | :---: | :--------: | :----------: | :-: | :-: | :------: |
+/// |   1   |     32     |      32      | 16 | 32  | variable |
+///
+/// The suite is the RFC 9381 suite string, `0x03` for TAI and `0x04` for ELL2. The public key
+/// must pass the same checks as in [`ED25519VERIFY`](crate::ed25519::ED25519VERIFY), so it must
+/// be canonically encoded and not of small order.
+///
+/// Gas is charged as [`ECVRFVERIFY_BASE`] plus [`ECVRFVERIFY_PER_WORD`] for every 32-byte word of
+/// alpha. The ELL2 suite also charges [`ECVRF_ELL2_ENCODE`], and the TAI suite charges
+/// [`ECVRF_TAI_PER_ATTEMPT`], plus alpha again, for every attempt after the first at encoding
+/// alpha to the curve.
+fn ecvrf_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let mut meter = GasMeter::new(gas_limit);
+    let alpha_len = input.len().saturating_sub(ECVRFVERIFY_PREFIX_LEN);
+    meter.charge(calc_linear_cost_u32(
+        alpha_len,
+        ECVRFVERIFY_BASE,
+        ECVRFVERIFY_PER_WORD,
+    ))?;
+    let beta = verify_impl(input, &mut meter)?;
+    let output = beta.map(|beta| beta.to_vec().into()).unwrap_or_default();
+    Ok(PrecompileOutput::new(meter.used, output))
+}
+
+/// Returns the VRF output if the proof included in the input byte slice is valid, `None`
+/// otherwise, failing if `meter` runs out of gas.
+fn verify_impl(input: &[u8], meter: &mut GasMeter) -> Result<Option<[u8; 64]>, PrecompileErrors> {
+    if input.len() < ECVRFVERIFY_PREFIX_LEN {
+        return Ok(None);
+    }
+
+    let suite = input[0];
+    let pk: &[u8; 32] = input[1..33].try_into().unwrap();
+    let gamma_bytes: &[u8; 32] = input[33..65].try_into().unwrap();
+    let c_bytes = &input[65..81];
+    let s_bytes: [u8; 32] = input[81..113].try_into().unwrap();
+    let alpha = &input[ECVRFVERIFY_PREFIX_LEN..];
+
+    let Some(y) = strict_public_key(pk) else {
+        return Ok(None);
+    };
+    let Some(gamma) = decompress_canonical(gamma_bytes) else {
+        return Ok(None);
+    };
+    let mut c = [0; 32];
+    c[..ECVRF_CHALLENGE_LEN].copy_from_slice(c_bytes);
+    let c = Scalar::from_bytes_mod_order(c);
+    let Some(s) = Option::<Scalar>::from(Scalar::from_canonical_bytes(s_bytes)) else {
+        return Ok(None);
+    };
+
+    let h = match suite {
+        ECVRF_EDWARDS25519_SHA512_TAI => encode_to_curve_tai(pk, alpha, meter)?,
+        ECVRF_EDWARDS25519_SHA512_ELL2 => {
+            meter.charge(ECVRF_ELL2_ENCODE)?;
+            Some(encode_to_curve_ell2(pk, alpha))
+        }
+        _ => None,
+    };
+    let Some(h) = h else {
+        return Ok(None);
+    };
+
+    // U = s * B - c * Y, V = s * H - c * Gamma
+    let u = EdwardsPoint::vartime_double_scalar_mul_basepoint(&-c, &y, &s);
+    let v = EdwardsPoint::vartime_multiscalar_mul([s, -c], [h, gamma]);
+
+    let challenge = Sha512::new()
+        .chain_update([suite, 0x02])
+        .chain_update(pk)
+        .chain_update(h.compress().as_bytes())
+        .chain_update(gamma_bytes)
+        .chain_update(u.compress().as_bytes())
+        .chain_update(v.compress().as_bytes())
+        .chain_update([0x00])
+        .finalize();
+    if challenge[..ECVRF_CHALLENGE_LEN] != *c_bytes {
+        return Ok(None);
+    }
+
+    let beta = Sha512::new()
+        .chain_update([suite, 0x03])
+        .chain_update(gamma.mul_by_cofactor().compress().as_bytes())
+        .chain_update([0x00])
+        .finalize();
+    Ok(Some(beta.into()))
+}
+
+/// `ECVRF_encode_to_curve_try_and_increment`, charging `meter` for every attempt after the
+/// first. Returns `None` if no attempt produces a point.
+fn encode_to_curve_tai(
+    pk: &[u8; 32],
+    alpha: &[u8],
+    meter: &mut GasMeter,
+) -> Result<Option<EdwardsPoint>, PrecompileErrors> {
+    for ctr in 0..=u8::MAX {
+        if ctr > 0 {
+            meter.charge(calc_linear_cost_u32(
+                alpha.len(),
+                ECVRF_TAI_PER_ATTEMPT,
+                ECVRFVERIFY_PER_WORD,
+            ))?;
+        }
+        let hash = Sha512::new()
+            .chain_update([ECVRF_EDWARDS25519_SHA512_TAI, 0x01])
+            .chain_update(pk)
+            .chain_update(alpha)
+            .chain_update([ctr, 0x00])
+            .finalize();
+        if let Some(h) = decompress_canonical(hash[..32].try_into().unwrap()) {
+            return Ok(Some(h.mul_by_cofactor()));
+        }
+    }
+    Ok(None)
+}
+
+/// `ECVRF_encode_to_curve_h2c_suite` with the `edwards25519_XMD:SHA-512_ELL2_NU_` suite.
+fn encode_to_curve_ell2(pk: &[u8; 32], alpha: &[u8]) -> EdwardsPoint {
+    let dst = [
+        b"ECVRF_",
+        ELL2_H2C_SUITE_ID,
+        &[ECVRF_EDWARDS25519_SHA512_ELL2],
+    ]
+    .concat();
+    encode_to_edwards25519(&[pk, alpha].concat(), &dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::hex;
+
+    /// RFC 9381 B.3 vectors as `(public key, alpha, pi, beta)`.
+    type Vector = ([u8; 32], &'static [u8], [u8; 80], [u8; 64]);
+
+    /// Public keys of the RFC 8032 test keys that the vectors use.
+    const PUBLIC_KEYS: [[u8; 32]; 3] = [
+        hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+        hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+        hex!("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025"),
+    ];
+
+    /// Example 16 to 18, for the TAI suite.
+    const TAI_VECTORS: [Vector; 3] = [
+        (
+            PUBLIC_KEYS[0],
+            b"",
+            hex!(
+                "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f"
+                "26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab12"
+                "68a1b0db10836d9826a528ca76567805"
+            ),
+            hex!(
+                "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff"
+                "66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae"
+            ),
+        ),
+        (
+            PUBLIC_KEYS[1],
+            b"\x72",
+            hex!(
+                "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed593"
+                "3bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926d"
+                "a3ef39226bbc355bdc9850112c8f4b02"
+            ),
+            hex!(
+                "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb"
+                "5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031"
+            ),
+        ),
+        (
+            PUBLIC_KEYS[2],
+            b"\xaf\x82",
+            hex!(
+                "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf80"
+                "96bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a"
+                "2d41b00b05081ed0f58ee5e31b3a970e"
+            ),
+            hex!(
+                "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c45"
+                "2118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f"
+            ),
+        ),
+    ];
+
+    /// Example 19 to 21, for the ELL2 suite.
+    const ELL2_VECTORS: [Vector; 3] = [
+        (
+            PUBLIC_KEYS[0],
+            b"",
+            hex!(
+                "7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f"
+                "14adf9a3cd8b8412d9038531e865c341cafa73589b023d14311c331a9ad15ff2"
+                "fb37831e00f0acaa6d73bc9997b06501"
+            ),
+            hex!(
+                "9d574bf9b8302ec0fc1e21c3ec5368269527b87b462ce36dab2d14ccf80c53cc"
+                "cf6758f058c5b1c856b116388152bbe509ee3b9ecfe63d93c3b4346c1fbc6c54"
+            ),
+        ),
+        (
+            PUBLIC_KEYS[1],
+            b"\x72",
+            hex!(
+                "47b327393ff2dd81336f8a2ef10339112401253b3c714eeda879f12c509072ef"
+                "055b48372bb82efbdce8e10c8cb9a2f9d60e93908f93df1623ad78a86a028d6b"
+                "c064dbfc75a6a57379ef855dc6733801"
+            ),
+            hex!(
+                "38561d6b77b71d30eb97a062168ae12b667ce5c28caccdf76bc88e093e463598"
+                "7cd96814ce55b4689b3dd2947f80e59aac7b7675f8083865b46c89b2ce9cc735"
+            ),
+        ),
+        (
+            PUBLIC_KEYS[2],
+            b"\xaf\x82",
+            hex!(
+                "926e895d308f5e328e7aa159c06eddbe56d06846abf5d98c2512235eaa57fdce"
+                "35b46edfc655bc828d44ad09d1150f31374e7ef73027e14760d42e77341fe054"
+                "67bb286cc2c9d7fde29120a0b2320d04"
+            ),
+            hex!(
+                "121b7f9b9aaaa29099fc04a94ba52784d44eac976dd1a3cca458733be5cd090a"
+                "7b5fbd148444f17f8daf1fb55cb04b1ae85a626e30a54b4b0f8abf4a43314a58"
+            ),
+        ),
+    ];
+
+    /// Encodes the input of [`ECVRFVERIFY`].
+    fn input(suite: u8, (pk, alpha, pi, _): &Vector) -> Bytes {
+        [&[suite], pk.as_slice(), pi, alpha].concat().into()
+    }
+
+    #[test]
+    fn tai_vectors() {
+        for vector in &TAI_VECTORS {
+            let output = ecvrf_verify(&input(ECVRF_EDWARDS25519_SHA512_TAI, vector), u64::MAX);
+            assert_eq!(output.unwrap().bytes.as_ref(), vector.3);
+        }
+    }
+
+    #[test]
+    fn ell2_vectors() {
+        for vector in &ELL2_VECTORS {
+            let output = ecvrf_verify(&input(ECVRF_EDWARDS25519_SHA512_ELL2, vector), u64::MAX);
+            assert_eq!(output.unwrap().bytes.as_ref(), vector.3);
+        }
+    }
+
+    #[test]
+    fn rejects_proof_of_other_suite() {
+        // a TAI proof does not verify as an ELL2 proof, and the output is then empty
+        let output = ecvrf_verify(
+            &input(ECVRF_EDWARDS25519_SHA512_ELL2, &TAI_VECTORS[0]),
+            u64::MAX,
+        );
+        assert!(output.unwrap().bytes.is_empty());
+    }
+}
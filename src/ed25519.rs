@@ -5,32 +5,332 @@
 //! The main purpose of this precompile is to verify ECDSA signatures that use the ed25519 elliptic
 //! curve. The [`ED25519VERIFY`](crate::ed25519::ED25519VERIFY) const represents the implementation
 //! of this precompile, with the address that it is currently deployed at.
+//!
+//! Implementations do not agree on which ed25519 signatures are valid, so the precompile can be
+//! built with any of the [`Ed25519VerifyMode`]s using [`ed25519_verify_precompile`].
+//! [`ED25519VERIFY_EIP665`] verifies the same signatures with the input layout, output and gas of
+//! [EIP-665](https://eips.ethereum.org/EIPS/eip-665), for tooling written against it, and
+//! [`ED25519KECCAKVERIFY`] verifies signatures over the EIP-191 digest of a `keccak256` hash, so
+//! contracts and signers do not need their own convention for what gets signed.
+//!
+//! The [`ED25519PHVERIFY`] const is a separate precompile for the Ed25519ph variant, which verifies
+//! signatures over a SHA-512 digest of the message instead of the message itself, and
+//! [`ED25519CTXVERIFY`] verifies Ed25519ctx signatures, which bind a context string to the message.
+//! [`ED25519BATCHVERIFY`] checks many signatures in a single call, and
+//! [`ED25519BATCHBITMAP`] does the same while reporting which of the signatures are valid.
+//! [`ED25519THRESHOLDVERIFY`] batch-verifies the signatures of a weighted k-of-n multisig and
+//! checks that the signers meet its threshold, and [`ED25519MERKLEVERIFY`] verifies a signature
+//! over a Merkle root together with the inclusion proof of one of its leaves.
+//! [`ED25519HALFAGGVERIFY`] verifies half-aggregated signatures, which take about half the
+//! calldata of separate ones.
+//! [`ED25519PUBKEYVALIDATE`] checks a public key on its own and reports why it would be rejected,
+//! and [`ED25519TOX25519`] converts a public key to the X25519 key of the same secret.
 
-use crate::addresses::ED25519VERIFY_ADDRESS;
+use crate::{
+    addresses::{
+        ED25519BATCHBITMAP_ADDRESS, ED25519BATCHVERIFY_ADDRESS, ED25519CTXVERIFY_ADDRESS,
+        ED25519HALFAGGVERIFY_ADDRESS, ED25519KECCAKVERIFY_ADDRESS, ED25519MERKLEVERIFY_ADDRESS,
+        ED25519PHVERIFY_ADDRESS, ED25519PUBKEYVALIDATE_ADDRESS, ED25519THRESHOLDVERIFY_ADDRESS,
+        ED25519TOX25519_ADDRESS, ED25519VERIFY_ADDRESS, ED25519VERIFY_EIP665_ADDRESS,
+    },
+    field::{self, FieldElement},
+    utils::GasMeter,
+};
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT, edwards::CompressedEdwardsY, traits::VartimeMultiscalarMul,
+    EdwardsPoint, Scalar,
+};
 use ed25519::Signature;
-use ed25519_dalek::VerifyingKey;
+use ed25519_dalek::{Verifier, VerifyingKey};
 use revm::{
-    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    precompile::{
+        calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress,
+        StandardPrecompileFn,
+    },
     primitives::{
-        Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, B256,
+        keccak256, Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult,
+        B256,
     },
 };
-use sha2::Sha512VarCore;
+use sha2::{
+    digest::{consts::U64, FixedOutput, HashMarker, Output, OutputSizeUser, Update},
+    Digest, Sha256, Sha512, Sha512_256,
+};
 
 /// Base gas fee for ed25519verify operation.
 const ED25519VERIFY_BASE: u64 = 3_450;
 
-/// Returns the ed25519 precompile with its address.
+/// Gas fee charged for every 32-byte word of the signed message, rounded up.
+const ED25519VERIFY_PER_WORD: u64 = 12;
+
+/// Length of the fixed-size prefix of the input: the signature (`r`, `s`) and the public key.
+const ED25519VERIFY_PREFIX_LEN: usize = 96;
+
+/// Gas fee for the EIP-665 ed25519verify operation.
+const ED25519VERIFY_EIP665_GAS: u64 = 2_000;
+
+/// Length of the EIP-665 input: the message, the public key and the signature.
+const ED25519VERIFY_EIP665_INPUT_LEN: usize = 128;
+
+/// Output of the EIP-665 precompile when the signature is not valid. The EIP only requires it to
+/// be non-zero.
+const ED25519VERIFY_EIP665_FAILURE: [u8; 4] = [0xff; 4];
+
+/// Gas fee for ed25519keccakverify operation: the fee of [`ED25519VERIFY`] for a 32-byte message
+/// (3,462), plus the `KECCAK256` opcode fee for the 60-byte prefixed hash (30 + 2 * 6).
+const ED25519KECCAKVERIFY_GAS: u64 = 3_504;
+
+/// Length of the ed25519keccakverify input: the signature, the public key and the hash.
+const ED25519KECCAKVERIFY_INPUT_LEN: usize = 128;
+
+/// The EIP-191 `0x45` prefix that the hash is signed under, as in `eth_sign`.
+const ETH_SIGNED_MESSAGE_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+/// Length of the fixed-size prefix of the Ed25519ph input: the digest, the signature and the
+/// public key.
+const ED25519PHVERIFY_PREFIX_LEN: usize = 160;
+
+/// Base gas fee for ed25519batchverify operation, charged once per call, so that a batch of one
+/// costs about as much as [`ED25519VERIFY`].
+const ED25519BATCHVERIFY_BASE: u64 = 2_100;
+
+/// Gas fee charged for every signature in an ed25519batchverify call, on top of
+/// [`ED25519VERIFY_PER_WORD`] for every 32-byte word of its message. The multi-scalar
+/// multiplication of a batch costs less than half as much per signature as separate checks.
+const ED25519BATCHVERIFY_PER_SIGNATURE: u64 = 1_400;
+
+/// Gas fee charged for checking a signature of an ed25519batchbitmap call on its own after the
+/// batch failed, which takes a double scalar multiplication on top of the decoding the batch did.
+const ED25519BATCHBITMAP_PER_FALLBACK: u64 = 2_500;
+
+/// Domain separator of the transcript that the coefficients of a batch are derived from.
+const BATCH_DOMAIN: &[u8] = b"Ed25519 batch verification";
+
+/// Length of the random coefficients of batch equations, which are 128-bit scalars.
+const COEFFICIENT_LEN: usize = 16;
+
+/// Length of the big-endian message length that follows each public key in a batch.
+const ED25519BATCHVERIFY_MSG_LEN_LEN: usize = 4;
+
+/// Gas fee charged for every `(public key, weight)` entry of an ed25519thresholdverify call, on
+/// top of the fees of [`ED25519BATCHVERIFY`] for its signatures.
+const ED25519THRESHOLDVERIFY_PER_KEY: u64 = 20;
+
+/// Length of a `(public key, weight)` entry of an ed25519thresholdverify call.
+const THRESHOLD_KEY_LEN: usize = 40;
+
+/// Length of a `(signer index, signature)` pair of an ed25519thresholdverify call.
+const THRESHOLD_SIGNATURE_LEN: usize = 65;
+
+/// Base gas fee for ed25519merkleverify operation: the fee of [`ED25519VERIFY`] for the 32-byte
+/// root.
+const ED25519MERKLEVERIFY_BASE: u64 = ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD;
+
+/// Gas fee charged for every level of a Merkle proof, which hashes 65 bytes.
+const ED25519MERKLEVERIFY_PER_LEVEL: u64 = 100;
+
+/// Length of the fixed-size prefix of the ed25519merkleverify input: the hash function, the
+/// signature, the public key, the leaf index and the proof depth.
+const ED25519MERKLEVERIFY_PREFIX_LEN: usize = 106;
+
+/// Maximum depth of a Merkle proof, bounded by the 8-byte leaf index.
+const MERKLE_MAX_DEPTH: usize = 64;
+
+/// Selector of SHA-256 as the Merkle tree hash function.
+const MERKLE_SHA256: u8 = 0x00;
+/// Selector of Keccak-256 as the Merkle tree hash function.
+const MERKLE_KECCAK256: u8 = 0x01;
+/// Selector of SHA-512/256 as the Merkle tree hash function.
+const MERKLE_SHA512_256: u8 = 0x02;
+
+/// Byte prepended to a leaf before hashing it, as in RFC 6962.
+const MERKLE_LEAF_PREFIX: u8 = 0x00;
+/// Byte prepended to a pair of nodes before hashing them, as in RFC 6962.
+const MERKLE_NODE_PREFIX: u8 = 0x01;
+
+/// Length of the fixed-size prefix of each entry of a half-aggregated signature: the `R`
+/// component, the public key and the message length.
+const HALFAGG_ENTRY_PREFIX_LEN: usize = 68;
+
+/// Domain separator of the transcript that the half-aggregation coefficients are derived from.
+const HALFAGG_DOMAIN: &[u8] = b"Ed25519 half-aggregation";
+
+/// Gas fee for ed25519pubkeyvalidate operation, which is dominated by the scalar multiplication
+/// that checks for a torsion component.
+const ED25519PUBKEYVALIDATE_GAS: u64 = 2_500;
+
+/// Gas fee for ed25519tox25519 operation.
+const ED25519TOX25519_GAS: u64 = 1_000;
+
+/// Prefix of the `dom2` domain separator that RFC 8032 prepends to Ed25519ctx and Ed25519ph
+/// challenges.
+const DOM2_PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
+
+/// The rules used to decide whether an ed25519 signature is valid.
+///
+/// RFC 8032 leaves some room for interpretation, and widely deployed verifiers disagree on
+/// non-canonical encodings and small-order points. Picking the mode that matches the other
+/// verifiers of a signature keeps the precompile in consensus with them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Ed25519VerifyMode {
+    /// RFC 8032 verification with the cofactorless equation `[s]B = R + [k]A`.
+    ///
+    /// Rejects non-canonical `s` and public key encodings, and rejects small-order public keys
+    /// and `R` components. This is the mode [`ED25519VERIFY`] is built with.
+    #[default]
+    Strict,
+    /// [ZIP-215](https://zips.z.cash/zip-0215) verification with the cofactored equation
+    /// `[8][s]B = [8]R + [8][k]A`.
+    ///
+    /// Accepts non-canonical and small-order encodings of the public key and `R`, but requires a
+    /// canonical `s`. This matches Zcash and Tendermint (`ed25519-consensus`).
+    Zip215,
+    /// The semantics of ed25519-dalek's `VerifyingKey::verify`, as used by Solana.
+    ///
+    /// Cofactorless and requires a canonical `s`, but accepts small-order public keys and `R`
+    /// components.
+    Legacy,
+}
+
+/// The result of validating an ed25519 public key with [`ED25519PUBKEYVALIDATE`], returned as the
+/// last byte of a 32-byte word.
+///
+/// The checks are made in the order of the variants, and the first one that fails is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Ed25519KeyStatus {
+    /// The key is a canonical encoding of a point in the prime-order subgroup, and is accepted by
+    /// every [`Ed25519VerifyMode`].
+    Valid = 0,
+    /// The encoding does not decompress to a point on the curve.
+    NotOnCurve = 1,
+    /// The point is on the curve but its encoding is not canonical: the y coordinate is not
+    /// reduced, or the sign bit is set for an x coordinate of zero.
+    NonCanonical = 2,
+    /// The point has small order, so signatures under it can be forged without a secret key.
+    SmallOrder = 3,
+    /// The point has a component in the torsion subgroup, so verifiers using the cofactored and
+    /// cofactorless equations can disagree about signatures under it.
+    MixedOrder = 4,
+}
+
+/// Returns the ed25519 precompiles with their addresses.
 pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
-    [ED25519VERIFY].into_iter()
+    [
+        ED25519VERIFY,
+        ED25519VERIFY_EIP665,
+        ED25519KECCAKVERIFY,
+        ED25519PHVERIFY,
+        ED25519CTXVERIFY,
+        ED25519BATCHVERIFY,
+        ED25519BATCHBITMAP,
+        ED25519THRESHOLDVERIFY,
+        ED25519MERKLEVERIFY,
+        ED25519HALFAGGVERIFY,
+        ED25519PUBKEYVALIDATE,
+        ED25519TOX25519,
+    ]
+    .into_iter()
+}
+
+/// ed25519 precompile, using [`Ed25519VerifyMode::Strict`].
+pub const ED25519VERIFY: PrecompileWithAddress =
+    ed25519_verify_precompile(Ed25519VerifyMode::Strict);
+
+/// Returns the ed25519 precompile, at its address, deciding validity with the given mode.
+pub const fn ed25519_verify_precompile(mode: Ed25519VerifyMode) -> PrecompileWithAddress {
+    let run: StandardPrecompileFn = match mode {
+        Ed25519VerifyMode::Strict => ed25519_verify_strict,
+        Ed25519VerifyMode::Zip215 => ed25519_verify_zip215,
+        Ed25519VerifyMode::Legacy => ed25519_verify_legacy,
+    };
+    PrecompileWithAddress(
+        u64_to_address(ED25519VERIFY_ADDRESS),
+        Precompile::Standard(run),
+    )
 }
 
-/// ed25519 precompile.
-pub const ED25519VERIFY: PrecompileWithAddress = PrecompileWithAddress(
-    u64_to_address(ED25519VERIFY_ADDRESS),
-    Precompile::Standard(ed25519_verify),
+/// EIP-665 ed25519 precompile.
+pub const ED25519VERIFY_EIP665: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519VERIFY_EIP665_ADDRESS),
+    Precompile::Standard(ed25519_verify_eip665),
 );
 
+/// ed25519 precompile for signatures over an EIP-191 `keccak256` digest.
+pub const ED25519KECCAKVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519KECCAKVERIFY_ADDRESS),
+    Precompile::Standard(ed25519_keccak_verify),
+);
+
+/// Ed25519ph precompile.
+pub const ED25519PHVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519PHVERIFY_ADDRESS),
+    Precompile::Standard(ed25519ph_verify),
+);
+
+/// Ed25519ctx precompile.
+pub const ED25519CTXVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519CTXVERIFY_ADDRESS),
+    Precompile::Standard(ed25519ctx_verify),
+);
+
+/// ed25519 batch verification precompile.
+pub const ED25519BATCHVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519BATCHVERIFY_ADDRESS),
+    Precompile::Standard(ed25519_batch_verify),
+);
+
+/// ed25519 batch verification precompile that reports the result of each signature.
+pub const ED25519BATCHBITMAP: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519BATCHBITMAP_ADDRESS),
+    Precompile::Standard(ed25519_batch_bitmap),
+);
+
+/// ed25519 weighted threshold multisig precompile.
+pub const ED25519THRESHOLDVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519THRESHOLDVERIFY_ADDRESS),
+    Precompile::Standard(ed25519_threshold_verify),
+);
+
+/// ed25519 precompile for signed Merkle roots.
+pub const ED25519MERKLEVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519MERKLEVERIFY_ADDRESS),
+    Precompile::Standard(ed25519_merkle_verify),
+);
+
+/// ed25519 half-aggregated signature verification precompile.
+pub const ED25519HALFAGGVERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519HALFAGGVERIFY_ADDRESS),
+    Precompile::Standard(ed25519_halfagg_verify),
+);
+
+/// ed25519 public key validation precompile.
+pub const ED25519PUBKEYVALIDATE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519PUBKEYVALIDATE_ADDRESS),
+    Precompile::Standard(ed25519_pubkey_validate),
+);
+
+/// Ed25519 to X25519 public key conversion precompile.
+pub const ED25519TOX25519: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(ED25519TOX25519_ADDRESS),
+    Precompile::Standard(ed25519_to_x25519),
+);
+
+/// [`ed25519_verify`] with [`Ed25519VerifyMode::Strict`].
+fn ed25519_verify_strict(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    ed25519_verify(input, gas_limit, Ed25519VerifyMode::Strict)
+}
+
+/// [`ed25519_verify`] with [`Ed25519VerifyMode::Zip215`].
+fn ed25519_verify_zip215(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    ed25519_verify(input, gas_limit, Ed25519VerifyMode::Zip215)
+}
+
+/// [`ed25519_verify`] with [`Ed25519VerifyMode::Legacy`].
+fn ed25519_verify_legacy(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    ed25519_verify(input, gas_limit, Ed25519VerifyMode::Legacy)
+}
+
 /// ed25519 precompile logic. It takes the input bytes sent to the precompile
 /// and the gas limit. The output represents the result of verifying the
 /// ed25519 signature of the input.
@@ -40,46 +340,1928 @@ pub const ED25519VERIFY: PrecompileWithAddress = PrecompileWithAddress(
 ///
 /// The input is encoded as follows:
 ///
-/// | signed message hash |  r  |  s  | public key  |
-/// | :-----------------: | :-: | :-: | :---------: |
-/// |          64         | 32  | 32  |     32      |
-fn ed25519_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    if ED25519VERIFY_BASE > gas_limit {
+/// |  r  |  s  | public key  | message  |
+/// | :-: | :-: | :---------: | :------: |
+/// | 32  | 32  |     32      | variable |
+///
+/// The message is the raw message that was signed, of any length, and is hashed by the
+/// precompile as part of the RFC 8032 verification. Gas is charged as [`ED25519VERIFY_BASE`] plus
+/// [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the message.
+fn ed25519_verify(input: &Bytes, gas_limit: u64, mode: Ed25519VerifyMode) -> PrecompileResult {
+    let msg_len = input.len().saturating_sub(ED25519VERIFY_PREFIX_LEN);
+    let gas_used = calc_linear_cost_u32(msg_len, ED25519VERIFY_BASE, ED25519VERIFY_PER_WORD);
+    if gas_used > gas_limit {
         return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
     }
-    let result = verify_impl(input).is_some();
+    let result = verify_impl(input, mode).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Returns `Some(())` if the signature included in the input byte slice is
+/// valid under the given mode, `None` otherwise.
+fn verify_impl(input: &[u8], mode: Ed25519VerifyMode) -> Option<()> {
+    if input.len() < ED25519VERIFY_PREFIX_LEN {
+        return None;
+    }
+
+    // r, s: signature
+    let sig: &[u8; 64] = input[..64].try_into().unwrap();
+    // public key
+    let pk: &[u8; 32] = input[64..96].try_into().unwrap();
+    // raw message, the precompile does the hashing
+    let msg = &input[ED25519VERIFY_PREFIX_LEN..];
+
+    verify_signature(sig, pk, msg, mode)
+}
+
+/// Returns `Some(())` if `sig` is a valid signature of `msg` by the public key `pk` under the given
+/// mode, `None` otherwise.
+fn verify_signature(
+    sig: &[u8; 64],
+    pk: &[u8; 32],
+    msg: &[u8],
+    mode: Ed25519VerifyMode,
+) -> Option<()> {
+    match mode {
+        Ed25519VerifyMode::Strict => {
+            // Can fail if the input is not valid, so we have to propagate the error.
+            let public_key = strict_verifying_key(pk)?;
+
+            // we do not use verify_prehashed because weak keys are bad, and `verify_strict`
+            // rejects them along with small order `R` components and non-canonical `s`.
+            // we do not use a domain separator, although it may be valid
+            public_key
+                .verify_strict(msg, &Signature::from_bytes(sig))
+                .ok()
+        }
+        Ed25519VerifyMode::Zip215 => verify_zip215(sig, pk, msg),
+        Ed25519VerifyMode::Legacy => {
+            let public_key = VerifyingKey::from_bytes(pk).ok()?;
+            public_key.verify(msg, &Signature::from_bytes(sig)).ok()
+        }
+    }
+}
+
+/// Public key validation precompile logic. It takes the input bytes sent to the precompile and the
+/// gas limit. The output is a 32-byte word whose last byte is the [`Ed25519KeyStatus`] of the
+/// public key in the input.
+///
+/// The input is the 32-byte encoded public key, and any other length is an error. Gas is
+/// [`ED25519PUBKEYVALIDATE_GAS`].
+fn ed25519_pubkey_validate(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if ED25519PUBKEYVALIDATE_GAS > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let pk: &[u8; 32] = input.as_ref().try_into().map_err(|_| {
+        PrecompileError::other(format!(
+            "ed25519: public key should be 32 bytes, got {}",
+            input.len()
+        ))
+    })?;
+    let status = validate_public_key(pk);
     let out = PrecompileOutput::new(
-        ED25519VERIFY_BASE,
+        ED25519PUBKEYVALIDATE_GAS,
+        B256::with_last_byte(status as u8).into(),
+    );
+    Ok(out)
+}
+
+/// Returns the [`Ed25519KeyStatus`] of the public key `pk`.
+fn validate_public_key(pk: &[u8; 32]) -> Ed25519KeyStatus {
+    // this is the decoding that verification starts with, which accepts non-canonical encodings
+    let Ok(public_key) = VerifyingKey::from_bytes(pk) else {
+        return Ed25519KeyStatus::NotOnCurve;
+    };
+    let Some(point) = decompress_canonical(pk) else {
+        return Ed25519KeyStatus::NonCanonical;
+    };
+    if public_key.is_weak() {
+        Ed25519KeyStatus::SmallOrder
+    } else if !point.is_torsion_free() {
+        Ed25519KeyStatus::MixedOrder
+    } else {
+        Ed25519KeyStatus::Valid
+    }
+}
+
+/// EIP-665 ed25519 precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit. The output represents the result of verifying the ed25519 signature of the input, as
+/// specified by EIP-665.
+///
+/// The input is encoded as follows:
+///
+/// | message | public key |  r  |  s  |
+/// | :-----: | :--------: | :-: | :-: |
+/// |   32    |     32     | 32  | 32  |
+///
+/// The output is four zero bytes if the signature is valid under [`Ed25519VerifyMode::Strict`],
+/// and `0xffffffff` otherwise. Any other input length is an error. Gas is
+/// [`ED25519VERIFY_EIP665_GAS`].
+fn ed25519_verify_eip665(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if ED25519VERIFY_EIP665_GAS > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    if input.len() != ED25519VERIFY_EIP665_INPUT_LEN {
+        return Err(PrecompileError::other(format!(
+            "ed25519: EIP-665 input should be {ED25519VERIFY_EIP665_INPUT_LEN} bytes, got {}",
+            input.len()
+        ))
+        .into());
+    }
+
+    let msg = &input[..32];
+    let pk: &[u8; 32] = input[32..64].try_into().unwrap();
+    let sig: &[u8; 64] = input[64..].try_into().unwrap();
+    let output = match verify_signature(sig, pk, msg, Ed25519VerifyMode::Strict) {
+        Some(()) => [0; 4],
+        None => ED25519VERIFY_EIP665_FAILURE,
+    };
+    Ok(PrecompileOutput::new(
+        ED25519VERIFY_EIP665_GAS,
+        output.to_vec().into(),
+    ))
+}
+
+/// ed25519keccakverify precompile logic. It takes the input bytes sent to the precompile and the
+/// gas limit. The output represents the result of verifying the ed25519 signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// |  r  |  s  | public key  | hash |
+/// | :-: | :-: | :---------: | :--: |
+/// | 32  | 32  |     32      |  32  |
+///
+/// The hash is typically the `keccak256` of the data a contract authorizes. The signed message is
+/// the 32-byte digest `keccak256("\x19Ethereum Signed Message:\n32" || hash)`, the same digest
+/// that `eth_sign` produces for a 32-byte message, and is verified with
+/// [`Ed25519VerifyMode::Strict`]. An input of any other length fails verification. Gas is
+/// [`ED25519KECCAKVERIFY_GAS`].
+fn ed25519_keccak_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if ED25519KECCAKVERIFY_GAS > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = keccak_verify_impl(input).is_some();
+    let out = PrecompileOutput::new(
+        ED25519KECCAKVERIFY_GAS,
         B256::with_last_byte(result as u8).into(),
     );
     Ok(out)
 }
 
-/// Returns `Some(())` if the signature included in the input byte slice is
-/// valid, `None` otherwise.
-fn verify_impl(input: &[u8]) -> Option<()> {
-    if input.len() < 160 {
+/// Returns `Some(())` if the signature included in the input byte slice is valid for the EIP-191
+/// digest of its hash, `None` otherwise.
+fn keccak_verify_impl(input: &[u8]) -> Option<()> {
+    if input.len() != ED25519KECCAKVERIFY_INPUT_LEN {
         return None;
     }
+    let sig: &[u8; 64] = input[..64].try_into().unwrap();
+    let pk: &[u8; 32] = input[64..96].try_into().unwrap();
+    let digest = keccak256([ETH_SIGNED_MESSAGE_PREFIX, &input[96..]].concat());
+    verify_signature(sig, pk, digest.as_slice(), Ed25519VerifyMode::Strict)
+}
 
-    // msg signed (msg is already the hash of the original message)
-    let msg: &[u8; 64] = input[..64].try_into().unwrap();
+/// Ed25519 to X25519 conversion precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output is the X25519 public key that corresponds to the ed25519 public
+/// key in the input, like libsodium's `crypto_sign_ed25519_pk_to_curve25519`.
+///
+/// The input is the 32-byte encoded public key, which must be one that [`ED25519VERIFY`] accepts:
+/// canonically encoded and not of small order. The output is the 32-byte little-endian
+/// u-coordinate of the point on Curve25519 that the birational map takes it to, or empty if the
+/// input is not a valid public key. Gas is [`ED25519TOX25519_GAS`].
+fn ed25519_to_x25519(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if ED25519TOX25519_GAS > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let output = to_x25519_impl(input)
+        .map(|u| u.to_vec().into())
+        .unwrap_or_default();
+    Ok(PrecompileOutput::new(ED25519TOX25519_GAS, output))
+}
+
+/// Returns the X25519 public key for the ed25519 public key in the input byte slice, or `None` if
+/// it is not a valid public key.
+fn to_x25519_impl(input: &[u8]) -> Option<[u8; 32]> {
+    let pk: &[u8; 32] = input.try_into().ok()?;
+    Some(strict_public_key(pk)?.to_montgomery().to_bytes())
+}
+
+/// Ed25519ph precompile logic. It takes the input bytes sent to the precompile and the gas limit.
+/// The output represents the result of verifying the Ed25519ph signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// | SHA-512 message digest |  r  |  s  | public key  | context length | context  |
+/// | :--------------------: | :-: | :-: | :---------: | :------------: | :------: |
+/// |           64           | 32  | 32  |     32      |   1, optional  | 0 to 255 |
+///
+/// When the input ends after the public key the context is empty. Gas is charged as
+/// [`ED25519VERIFY_BASE`] plus [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the context.
+fn ed25519ph_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let context_len = input.len().saturating_sub(ED25519PHVERIFY_PREFIX_LEN + 1);
+    let gas_used = calc_linear_cost_u32(context_len, ED25519VERIFY_BASE, ED25519VERIFY_PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = ed25519ph_verify_impl(input).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Returns `Some(())` if the Ed25519ph signature included in the input byte slice is valid,
+/// `None` otherwise.
+fn ed25519ph_verify_impl(input: &[u8]) -> Option<()> {
+    if input.len() < ED25519PHVERIFY_PREFIX_LEN {
+        return None;
+    }
+
+    // SHA-512 digest of the message, computed by the caller
+    let digest: &[u8; 64] = input[..64].try_into().unwrap();
     // r, s: signature
     let sig: &[u8; 64] = input[64..128].try_into().unwrap();
     // public key
     let pk: &[u8; 32] = input[128..160].try_into().unwrap();
+    // the context is length-prefixed, and the prefix must account for the rest of the input
+    let context = match input[ED25519PHVERIFY_PREFIX_LEN..].split_first() {
+        None => &[][..],
+        Some((&len, context)) if context.len() == len as usize => context,
+        Some(_) => return None,
+    };
+
+    let public_key = strict_verifying_key(pk)?;
+    public_key
+        .verify_prehashed_strict(
+            Sha512Prehash::from(digest),
+            Some(context),
+            &Signature::from_bytes(sig),
+        )
+        .ok()
+}
+
+/// Ed25519ctx precompile logic. It takes the input bytes sent to the precompile and the gas limit.
+/// The output represents the result of verifying the Ed25519ctx signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// | context length | context  |  r  |  s  | public key  | message  |
+/// | :------------: | :------: | :-: | :-: | :---------: | :------: |
+/// |       1        | 1 to 255 | 32  | 32  |     32      | variable |
+///
+/// RFC 8032 does not allow Ed25519ctx with an empty context, so such inputs never verify. Gas is
+/// charged as [`ED25519VERIFY_BASE`] plus [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the
+/// context and message.
+fn ed25519ctx_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let data_len = input.len().saturating_sub(ED25519VERIFY_PREFIX_LEN + 1);
+    let gas_used = calc_linear_cost_u32(data_len, ED25519VERIFY_BASE, ED25519VERIFY_PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = ed25519ctx_verify_impl(input).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Returns `Some(())` if the Ed25519ctx signature included in the input byte slice is valid,
+/// `None` otherwise.
+fn ed25519ctx_verify_impl(input: &[u8]) -> Option<()> {
+    let (&context_len, rest) = input.split_first()?;
+    let context_len = context_len as usize;
+    if context_len == 0 || rest.len() < context_len + ED25519VERIFY_PREFIX_LEN {
+        return None;
+    }
+
+    let (context, rest) = rest.split_at(context_len);
+    // r, s: signature
+    let sig: &[u8; 64] = rest[..64].try_into().unwrap();
+    // public key
+    let pk: &[u8; 32] = rest[64..96].try_into().unwrap();
+    // raw message, the precompile does the hashing
+    let msg = &rest[ED25519VERIFY_PREFIX_LEN..];
+
+    // dalek has no Ed25519ctx support, so this mirrors `verify_strict` with the `dom2` prefix
+    let a = decompress_canonical(pk)?;
+    let (r_bytes, s_bytes) = sig.split_at(32);
+    let r = CompressedEdwardsY::from_slice(r_bytes).ok()?.decompress()?;
+    if a.is_small_order() || r.is_small_order() {
+        return None;
+    }
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(s_bytes.try_into().unwrap()))?;
+
+    let dom2 = [DOM2_PREFIX, &[0, context_len as u8], context].concat();
+    let k = challenge(&dom2, r_bytes, pk, msg);
+
+    let expected_r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &-a, &s);
+    (expected_r.compress().as_bytes() == r_bytes).then_some(())
+}
+
+/// ed25519 batch verification precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output is 1 if every signature in the input is valid, and 0 otherwise.
+///
+/// The input is a non-empty sequence of entries, each encoded as follows:
+///
+/// |  r  |  s  | public key  | message length | message  |
+/// | :-: | :-: | :---------: | :------------: | :------: |
+/// | 32  | 32  |     32      |  4, big-endian | variable |
+///
+/// Gas is charged as [`ED25519BATCHVERIFY_BASE`], plus [`ED25519BATCHVERIFY_PER_SIGNATURE`] and
+/// [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the message for each entry. Malformed
+/// inputs are charged only the base fee.
+///
+/// Signatures are accepted following [`Ed25519VerifyMode::Zip215`], not the
+/// [`Ed25519VerifyMode::Strict`] rules of [`ED25519VERIFY`]: the batch equation is cofactored, as
+/// in `ed25519-consensus`, because a cofactorless one would accept some signatures that strict
+/// verification rejects depending on the random coefficients. The output is 1 exactly when every
+/// signature would pass [`ED25519VERIFY`] built with [`Ed25519VerifyMode::Zip215`], up to the
+/// negligible chance of a batch accepting an invalid one. Contracts that need the strict rules,
+/// which reject small-order and non-canonical encodings, should check the public keys with
+/// [`ED25519PUBKEYVALIDATE`] or call [`ED25519VERIFY`] instead.
+fn ed25519_batch_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let entries = parse_batch(input);
+    let gas_used = entries
+        .as_deref()
+        .map_or(ED25519BATCHVERIFY_BASE, batch_gas);
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = entries.is_some_and(|entries| batch_verify_impl(&entries).is_some());
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// ed25519 batch bitmap precompile logic. It takes the input bytes sent to the precompile and the
+/// gas limit. The output is a bitmap with one bit per entry of the input, set if the signature of
+/// the entry is valid.
+///
+/// The input is encoded as for [`ed25519_batch_verify`]. Bit `i` of the output is bit `i % 8`,
+/// counting from the least significant bit, of byte `i / 8`. Malformed inputs are charged only the
+/// base fee and return an empty output.
+///
+/// The whole batch is verified first, for the same gas as [`ed25519_batch_verify`], leaving out
+/// the entries that do not decode, whose bits are clear. Only if that fails is each decoded
+/// signature checked on its own, which reuses the points and challenges decoded for the batch and
+/// costs [`ED25519BATCHBITMAP_PER_FALLBACK`] more per signature, so a batch with an invalid
+/// signature costs a little more than separate calls. Signatures are accepted following
+/// [`Ed25519VerifyMode::Zip215`], as in [`ed25519_batch_verify`].
+fn ed25519_batch_bitmap(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some(entries) = parse_batch(input) else {
+        if ED25519BATCHVERIFY_BASE > gas_limit {
+            return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+        }
+        return Ok(PrecompileOutput::new(ED25519BATCHVERIFY_BASE, Bytes::new()));
+    };
+
+    let mut meter = GasMeter::new(gas_limit);
+    meter.charge(batch_gas(&entries))?;
+    let mut bitmap = vec![0u8; entries.len().div_ceil(8)];
+    let (indices, decoded): (Vec<_>, Vec<_>) = entries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, entry)| Some((i, decode_zip215(entry.sig, entry.pk, entry.msg)?)))
+        .unzip();
+    let decoded_entries: Vec<_> = indices.iter().map(|&i| entries[i]).collect();
+    if batch_equation_holds(&decoded_entries, &decoded) {
+        indices
+            .iter()
+            .for_each(|&i| set_bits(&mut bitmap, i..i + 1));
+    } else {
+        for (i, signature) in indices.into_iter().zip(&decoded) {
+            meter.charge(ED25519BATCHBITMAP_PER_FALLBACK)?;
+            if zip215_equation_holds(signature) {
+                set_bits(&mut bitmap, i..i + 1);
+            }
+        }
+    }
+    Ok(PrecompileOutput::new(meter.used, bitmap.into()))
+}
+
+/// Sets the bits of `bitmap` at the given indices.
+fn set_bits(bitmap: &mut [u8], indices: std::ops::Range<usize>) {
+    for i in indices {
+        bitmap[i / 8] |= 1 << (i % 8);
+    }
+}
+
+/// ed25519 threshold multisig precompile logic. It takes the input bytes sent to the precompile and
+/// the gas limit. The output is 1 if the signatures in the input are valid and the weight of their
+/// signers meets the threshold, and 0 otherwise.
+///
+/// The input is encoded as follows:
+///
+/// | threshold | key count |      keys      | signature count |      signatures      | message  |
+/// | :-------: | :-------: | :------------: | :-------------: | :------------------: | :------: |
+/// |     8     |     1     | 40 × key count |        1        | 65 × signature count | variable |
+///
+/// The threshold is a big-endian integer and must not be zero. Each key is a 32-byte public key
+/// followed by its weight, a big-endian 8-byte integer, so a k-of-n multisig gives every key a
+/// weight of 1 and a threshold of k. Each signature is the 1-byte index of its signer in the keys
+/// followed by an ed25519 signature (`r`, `s`) of the message, which all signers sign. A public key
+/// that is not canonically encoded or appears more than once in the keys, or a signer index that is
+/// out of range or appears more than once, makes the input malformed, so that no signer can count
+/// their weight twice.
+///
+/// The signatures are verified together as in [`ed25519_batch_verify`], following
+/// [`Ed25519VerifyMode::Zip215`], and any invalid signature makes the output 0, so only the
+/// signatures that count towards the threshold should be included. Small-order keys are not
+/// rejected, and anyone can sign for them, so the keys should be checked with
+/// [`ED25519PUBKEYVALIDATE`] when the multisig is set up. Gas is charged as for an
+/// [`ED25519BATCHVERIFY`] call with the same signatures, plus [`ED25519THRESHOLDVERIFY_PER_KEY`]
+/// for every key, which makes checking two or more signatures cheaper than separate
+/// [`ED25519VERIFY`] calls. Malformed inputs are charged only [`ED25519BATCHVERIFY_BASE`].
+fn ed25519_threshold_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let multisig = parse_threshold(input);
+    let gas_used = multisig
+        .as_ref()
+        .map_or(ED25519BATCHVERIFY_BASE, |multisig| {
+            batch_gas(&multisig.entries)
+                + ED25519THRESHOLDVERIFY_PER_KEY * multisig.key_count as u64
+        });
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = multisig.is_some_and(|multisig| {
+        multisig.weight >= multisig.threshold && batch_verify_impl(&multisig.entries).is_some()
+    });
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// A threshold multisig, as passed to [`ED25519THRESHOLDVERIFY`].
+struct ThresholdMultisig<'a> {
+    /// weight that the signers must reach
+    threshold: u64,
+    /// number of keys in the multisig, signing or not
+    key_count: usize,
+    /// total weight of the signers, saturating
+    weight: u64,
+    /// the signatures, with the key of their signer
+    entries: Vec<BatchEntry<'a>>,
+}
+
+/// Parses the input of the threshold precompile, returning `None` if it is malformed.
+fn parse_threshold(input: &[u8]) -> Option<ThresholdMultisig<'_>> {
+    let (threshold, rest) = input.split_first_chunk::<8>()?;
+    let threshold = u64::from_be_bytes(*threshold);
+    if threshold == 0 {
+        return None;
+    }
+    let (&key_count, rest) = rest.split_first()?;
+    let key_count = key_count as usize;
+    let (keys, rest) = rest.split_at_checked(key_count * THRESHOLD_KEY_LEN)?;
+    let (&signature_count, rest) = rest.split_first()?;
+    let (signatures, msg) =
+        rest.split_at_checked(signature_count as usize * THRESHOLD_SIGNATURE_LEN)?;
+
+    // a signer must not be able to count their weight twice by signing under two indices, or
+    // under two encodings of their key
+    let mut public_keys: Vec<&[u8; 32]> = keys
+        .chunks_exact(THRESHOLD_KEY_LEN)
+        .map(|key| key[..32].try_into().unwrap())
+        .collect();
+    if !public_keys.iter().all(|pk| is_canonical_encoding(pk)) {
+        return None;
+    }
+    public_keys.sort_unstable();
+    if public_keys.windows(2).any(|pair| pair[0] == pair[1]) {
+        return None;
+    }
+
+    let mut signed = [false; 256];
+    let mut weight = 0u64;
+    let mut entries = Vec::with_capacity(signature_count as usize);
+    for signature in signatures.chunks_exact(THRESHOLD_SIGNATURE_LEN) {
+        let index = signature[0] as usize;
+        if index >= key_count || signed[index] {
+            return None;
+        }
+        signed[index] = true;
+        let key = &keys[index * THRESHOLD_KEY_LEN..(index + 1) * THRESHOLD_KEY_LEN];
+        weight = weight.saturating_add(u64::from_be_bytes(key[32..].try_into().unwrap()));
+        entries.push(BatchEntry {
+            sig: signature[1..].try_into().unwrap(),
+            pk: key[..32].try_into().unwrap(),
+            msg,
+        });
+    }
+    Some(ThresholdMultisig {
+        threshold,
+        key_count,
+        weight,
+        entries,
+    })
+}
+
+/// ed25519merkleverify precompile logic. It takes the input bytes sent to the precompile and the
+/// gas limit. The output is 1 if the leaf of the input is included in a Merkle tree whose root is
+/// signed by the signature of the input, and 0 otherwise.
+///
+/// The input is encoded as follows:
+///
+/// | hash |  r  |  s  | public key | leaf index | depth |    siblings    |   leaf   |
+/// | :--: | :-: | :-: | :--------: | :--------: | :---: | :------------: | :------: |
+/// |  1   | 32  | 32  |     32     |      8     |   1   |   32 × depth   | variable |
+///
+/// The hash selects the hash function of the tree:
+///
+/// | selector | hash function |
+/// | :------: | :-----------: |
+/// |   0x00   | SHA-256       |
+/// |   0x01   | Keccak-256    |
+/// |   0x02   | SHA-512/256   |
+///
+/// As in [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962#section-2.1), the node of the leaf is
+/// the hash of `0x00 || leaf`, and each level of the proof hashes `0x01` followed by two 32-byte
+/// nodes, the sibling going on the left if the corresponding bit of the big-endian leaf index is
+/// set, counting from the least significant bit. The prefixes keep a pair of nodes from passing as
+/// a leaf. The depth is at most [`MERKLE_MAX_DEPTH`], and the index must be below `2^depth`.
+///
+/// The signature is over the 32-byte root, and is verified with [`Ed25519VerifyMode::Strict`].
+/// Gas is charged as [`ED25519MERKLEVERIFY_BASE`], plus [`ED25519MERKLEVERIFY_PER_LEVEL`] for every
+/// level of the proof and [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the leaf. Malformed
+/// inputs are charged only the base fee.
+fn ed25519_merkle_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let proof = parse_merkle_proof(input);
+    let gas_used = proof.as_ref().map_or(ED25519MERKLEVERIFY_BASE, |proof| {
+        calc_linear_cost_u32(
+            proof.leaf.len(),
+            ED25519MERKLEVERIFY_BASE,
+            ED25519VERIFY_PER_WORD,
+        ) + ED25519MERKLEVERIFY_PER_LEVEL * proof.siblings.len() as u64
+    });
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = proof.is_some_and(|proof| merkle_verify_impl(&proof).is_some());
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// A hash function of Merkle trees.
+type MerkleHash = fn(&[u8]) -> [u8; 32];
 
-    // Can fail only if the input is not exact length.
-    let signature = Signature::from_slice(sig).unwrap();
-    // Can fail if the input is not valid, so we have to propagate the error.
-    let public_key = VerifyingKey::from_bytes(&pk).ok()?;
-
-    // we do not use verify_prehashed because weak keys are bad
-    // we do not use a domain separator, although it may be valid
-    // TODO: dalek api doesnt support raw prehashed data unless we impl the trait
-    // TODO: meter based on unbounded input
-    // public_key
-    //     .verify_prehashed_strict(wrapper, None, &signature)
-    //     .ok()
-    todo!("accept unbounded input, meter with prehashed")
+/// A signed Merkle root and the inclusion proof of one of its leaves.
+struct MerkleProof<'a> {
+    /// hashes a node or a pair of nodes
+    hash: MerkleHash,
+    /// r, s: signature of the root
+    sig: &'a [u8; 64],
+    /// public key
+    pk: &'a [u8; 32],
+    /// position of the leaf in the tree
+    index: u64,
+    /// sibling nodes, from the leaf up
+    siblings: Vec<&'a [u8; 32]>,
+    /// raw leaf, the precompile does the hashing
+    leaf: &'a [u8],
+}
+
+/// Splits the input of the Merkle precompile into its parts, returning `None` if it is malformed.
+fn parse_merkle_proof(input: &[u8]) -> Option<MerkleProof<'_>> {
+    if input.len() < ED25519MERKLEVERIFY_PREFIX_LEN {
+        return None;
+    }
+    let hash: MerkleHash = match input[0] {
+        MERKLE_SHA256 => |data| Sha256::digest(data).into(),
+        MERKLE_KECCAK256 => |data| keccak256(data).0,
+        MERKLE_SHA512_256 => |data| Sha512_256::digest(data).into(),
+        _ => return None,
+    };
+    let index = u64::from_be_bytes(input[97..105].try_into().unwrap());
+    let depth = input[105] as usize;
+    if depth > MERKLE_MAX_DEPTH || index.checked_shr(depth as u32).unwrap_or(0) != 0 {
+        return None;
+    }
+    let (siblings, leaf) = input[ED25519MERKLEVERIFY_PREFIX_LEN..].split_at_checked(depth * 32)?;
+    Some(MerkleProof {
+        hash,
+        sig: input[1..65].try_into().unwrap(),
+        pk: input[65..97].try_into().unwrap(),
+        index,
+        siblings: siblings
+            .chunks_exact(32)
+            .map(|sibling| sibling.try_into().unwrap())
+            .collect(),
+        leaf,
+    })
+}
+
+/// Returns `Some(())` if the leaf is included under a root that the signature is valid for, `None`
+/// otherwise.
+fn merkle_verify_impl(proof: &MerkleProof<'_>) -> Option<()> {
+    let mut node = (proof.hash)(&[&[MERKLE_LEAF_PREFIX], proof.leaf].concat());
+    for (level, sibling) in proof.siblings.iter().enumerate() {
+        let (left, right) = if proof.index >> level & 1 == 0 {
+            (&node, *sibling)
+        } else {
+            (*sibling, &node)
+        };
+        let mut pair = [MERKLE_NODE_PREFIX; 65];
+        pair[1..33].copy_from_slice(left);
+        pair[33..].copy_from_slice(right);
+        node = (proof.hash)(&pair);
+    }
+    verify_signature(proof.sig, proof.pk, &node, Ed25519VerifyMode::Strict)
+}
+
+/// ed25519 half-aggregation precompile logic. It takes the input bytes sent to the precompile and
+/// the gas limit. The output is 1 if the half-aggregated signature in the input is valid for all of
+/// its `(public key, message)` pairs, and 0 otherwise.
+///
+/// A half-aggregated signature, as in [Chalkias et al.](https://eprint.iacr.org/2021/350), keeps
+/// the `R` component of every ed25519 signature and folds their `s` components into a single
+/// `s = sum(z_i * s_i) mod l`. The input is the aggregated `s` followed by a non-empty sequence of
+/// entries:
+///
+/// |  s  |  entries  |
+/// | :-: | :-------: |
+/// | 32  | remaining |
+///
+/// where each entry is encoded as follows:
+///
+/// |  r  | public key  | message length | message  |
+/// | :-: | :---------: | :------------: | :------: |
+/// | 32  |     32      |  4, big-endian | variable |
+///
+/// The coefficients are derived from the entries, which bind every `R`, public key and message:
+/// with `T = SHA-512("Ed25519 half-aggregation" || entries)`, `z_i` is the first 16 bytes of
+/// `SHA-512(T || i)`, with `i` the 4-byte big-endian index of the entry, read as a little-endian
+/// integer. The signature is valid if `[8][s]B = [8]sum(z_i * (R_i + [k_i]A_i))`, where `k_i` is
+/// the RFC 8032 challenge of the entry. As in [`ED25519BATCHVERIFY`], the `R` components and public
+/// keys follow [`Ed25519VerifyMode::Zip215`] rather than strict verification: any encoding that
+/// decompresses is accepted, including non-canonical and small-order ones, and `s` must be
+/// canonical.
+///
+/// Gas is charged as for an [`ED25519BATCHVERIFY`] call with the same messages, plus
+/// [`ED25519VERIFY_PER_WORD`] for every 32-byte word of the entries, which are hashed to derive the
+/// coefficients, so aggregating two or more signatures costs less than verifying them with separate
+/// [`ED25519VERIFY`] calls. Malformed inputs are charged only [`ED25519BATCHVERIFY_BASE`].
+fn ed25519_halfagg_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let aggregate = parse_half_aggregate(input);
+    let gas_used = aggregate
+        .as_ref()
+        .map_or(ED25519BATCHVERIFY_BASE, |aggregate| {
+            aggregate.entries.iter().fold(
+                calc_linear_cost_u32(
+                    aggregate.transcript.len(),
+                    ED25519BATCHVERIFY_BASE,
+                    ED25519VERIFY_PER_WORD,
+                ),
+                |gas, entry| {
+                    gas + calc_linear_cost_u32(
+                        entry.msg.len(),
+                        ED25519BATCHVERIFY_PER_SIGNATURE,
+                        ED25519VERIFY_PER_WORD,
+                    )
+                },
+            )
+        });
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = aggregate.is_some_and(|aggregate| halfagg_verify_impl(&aggregate).is_some());
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// A half-aggregated signature, as passed to [`ED25519HALFAGGVERIFY`].
+struct HalfAggregate<'a> {
+    /// aggregated s component
+    s: &'a [u8; 32],
+    /// the encoded entries, which the coefficients are derived from
+    transcript: &'a [u8],
+    /// the `(R, public key, message)` entries
+    entries: Vec<HalfAggEntry<'a>>,
+}
+
+/// A single `(R, public key, message)` entry of a half-aggregated signature.
+struct HalfAggEntry<'a> {
+    /// R component of the signature
+    r: &'a [u8; 32],
+    /// public key
+    pk: &'a [u8; 32],
+    /// raw message, the precompile does the hashing
+    msg: &'a [u8],
+}
+
+/// Splits the input of the half-aggregation precompile into its parts, returning `None` if there
+/// are no entries or it is malformed.
+fn parse_half_aggregate(input: &[u8]) -> Option<HalfAggregate<'_>> {
+    let (s, transcript) = input.split_first_chunk::<32>()?;
+    let mut rest = transcript;
+    let mut entries = Vec::new();
+    while !rest.is_empty() {
+        if rest.len() < HALFAGG_ENTRY_PREFIX_LEN {
+            return None;
+        }
+        let msg_len = u32::from_be_bytes(rest[64..HALFAGG_ENTRY_PREFIX_LEN].try_into().unwrap());
+        let msg = rest[HALFAGG_ENTRY_PREFIX_LEN..].get(..msg_len as usize)?;
+        entries.push(HalfAggEntry {
+            r: rest[..32].try_into().unwrap(),
+            pk: rest[32..64].try_into().unwrap(),
+            msg,
+        });
+        rest = &rest[HALFAGG_ENTRY_PREFIX_LEN + msg.len()..];
+    }
+    (!entries.is_empty()).then_some(HalfAggregate {
+        s,
+        transcript,
+        entries,
+    })
+}
+
+/// Returns `Some(())` if the half-aggregated signature is valid, `None` otherwise.
+fn halfagg_verify_impl(aggregate: &HalfAggregate<'_>) -> Option<()> {
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(*aggregate.s))?;
+    let transcript = Sha512::new()
+        .chain_update(HALFAGG_DOMAIN)
+        .chain_update(aggregate.transcript)
+        .finalize();
+
+    let n = aggregate.entries.len();
+    let mut scalars = Vec::with_capacity(2 * n + 1);
+    let mut points = Vec::with_capacity(2 * n + 1);
+    scalars.push(s);
+    points.push(ED25519_BASEPOINT_POINT);
+    for (i, entry) in aggregate.entries.iter().enumerate() {
+        // any encoding that decompresses is accepted, as in `decode_zip215`
+        let a = CompressedEdwardsY(*entry.pk).decompress()?;
+        let r = CompressedEdwardsY(*entry.r).decompress()?;
+        let k = challenge(&[], entry.r, entry.pk, entry.msg);
+
+        let z = random_coefficient(&transcript, i);
+        scalars.extend([-z, -(z * k)]);
+        points.extend([r, a]);
+    }
+    // the equation is cofactored, as in `batch_equation_holds`
+    EdwardsPoint::vartime_multiscalar_mul(scalars, points)
+        .is_small_order()
+        .then_some(())
+}
+
+/// A single `(signature, public key, message)` entry of a batch.
+#[derive(Clone, Copy)]
+struct BatchEntry<'a> {
+    /// r, s: signature
+    sig: &'a [u8; 64],
+    /// public key
+    pk: &'a [u8; 32],
+    /// raw message, the precompile does the hashing
+    msg: &'a [u8],
+}
+
+/// Splits the input of a batch precompile into its entries, returning `None` if it is empty or
+/// malformed.
+fn parse_batch(mut input: &[u8]) -> Option<Vec<BatchEntry<'_>>> {
+    let mut entries = Vec::new();
+    while !input.is_empty() {
+        let header_len = ED25519VERIFY_PREFIX_LEN + ED25519BATCHVERIFY_MSG_LEN_LEN;
+        if input.len() < header_len {
+            return None;
+        }
+        let msg_len = u32::from_be_bytes(input[96..header_len].try_into().unwrap()) as usize;
+        let msg = input[header_len..].get(..msg_len)?;
+        entries.push(BatchEntry {
+            sig: input[..64].try_into().unwrap(),
+            pk: input[64..96].try_into().unwrap(),
+            msg,
+        });
+        input = &input[header_len + msg_len..];
+    }
+    (!entries.is_empty()).then_some(entries)
+}
+
+/// Returns the gas charged for verifying the given batch entries.
+fn batch_gas(entries: &[BatchEntry<'_>]) -> u64 {
+    entries.iter().fold(ED25519BATCHVERIFY_BASE, |gas, entry| {
+        gas + calc_linear_cost_u32(
+            entry.msg.len(),
+            ED25519BATCHVERIFY_PER_SIGNATURE,
+            ED25519VERIFY_PER_WORD,
+        )
+    })
+}
+
+/// Returns `Some(())` if every signature in the batch is valid under the ZIP-215 rules, `None`
+/// otherwise.
+fn batch_verify_impl(entries: &[BatchEntry<'_>]) -> Option<()> {
+    let decoded = entries
+        .iter()
+        .map(|entry| decode_zip215(entry.sig, entry.pk, entry.msg))
+        .collect::<Option<Vec<_>>>()?;
+    batch_equation_holds(entries, &decoded).then_some(())
+}
+
+/// Returns `true` if the signatures of a batch, decoded into `decoded`, pass the batch equation.
+///
+/// The signatures are checked together with the cofactored equation
+/// `[8][sum(z_i * s_i)]B = [8]sum(z_i * R_i) + [8]sum(z_i * k_i * A_i)`, where the coefficients
+/// `z_i` are derived from all the signatures, public keys and challenges, so a signer cannot pick
+/// them. The cofactor clears any torsion components, so a batch passes exactly when every
+/// signature passes [`zip215_equation_holds`] on its own, up to a chance of `2^-128` that a batch
+/// with an invalid signature passes.
+fn batch_equation_holds(entries: &[BatchEntry<'_>], decoded: &[Zip215Signature]) -> bool {
+    let mut transcript = Sha512::new().chain_update(BATCH_DOMAIN);
+    for (entry, signature) in entries.iter().zip(decoded) {
+        // the challenge binds the message
+        Digest::update(&mut transcript, entry.sig);
+        Digest::update(&mut transcript, entry.pk);
+        Digest::update(&mut transcript, signature.k.as_bytes());
+    }
+    let transcript = transcript.finalize();
+
+    let mut scalars = Vec::with_capacity(2 * decoded.len() + 1);
+    let mut points = Vec::with_capacity(2 * decoded.len() + 1);
+    let mut b_coefficient = Scalar::ZERO;
+    for (i, signature) in decoded.iter().enumerate() {
+        let z = random_coefficient(&transcript, i);
+        b_coefficient += z * signature.s;
+        scalars.extend([-z, -(z * signature.k)]);
+        points.extend([signature.r, signature.a]);
+    }
+    scalars.push(b_coefficient);
+    points.push(ED25519_BASEPOINT_POINT);
+    EdwardsPoint::vartime_multiscalar_mul(scalars, points).is_small_order()
+}
+
+/// A [`Digest`] standing in for a SHA-512 hasher whose output was computed by the caller.
+///
+/// dalek verifies Ed25519ph signatures only when handed the hasher the message was fed to, so this
+/// returns the bytes written to it, up to 64, as the digest.
+#[derive(Clone, Default)]
+struct Sha512Prehash {
+    digest: Output<Sha512>,
+    len: usize,
+}
+
+impl From<&[u8; 64]> for Sha512Prehash {
+    fn from(digest: &[u8; 64]) -> Self {
+        let mut prehash = Self::default();
+        Update::update(&mut prehash, digest);
+        prehash
+    }
+}
+
+impl Update for Sha512Prehash {
+    fn update(&mut self, data: &[u8]) {
+        let n = data.len().min(self.digest.len() - self.len);
+        self.digest[self.len..self.len + n].copy_from_slice(&data[..n]);
+        self.len += n;
+    }
+}
+
+impl OutputSizeUser for Sha512Prehash {
+    type OutputSize = U64;
+}
+
+impl FixedOutput for Sha512Prehash {
+    fn finalize_into(self, out: &mut Output<Self>) {
+        *out = self.digest;
+    }
+}
+
+impl HashMarker for Sha512Prehash {}
+
+/// Parses a public key for strict verification, rejecting encodings that are not canonical.
+fn strict_verifying_key(pk: &[u8; 32]) -> Option<VerifyingKey> {
+    // we check the encoding ourselves because `VerifyingKey::from_bytes` accepts non-canonical y
+    // coordinates
+    decompress_canonical(pk)?;
+    VerifyingKey::from_bytes(pk).ok()
+}
+
+/// Decodes a public key with the checks that strict verification makes, rejecting encodings that
+/// are not canonical and points of small order.
+pub(crate) fn strict_public_key(pk: &[u8; 32]) -> Option<EdwardsPoint> {
+    let point = decompress_canonical(pk)?;
+    (!point.is_small_order()).then_some(point)
+}
+
+/// Verifies a signature following the ZIP-215 rules, which dalek does not implement.
+fn verify_zip215(sig: &[u8; 64], pk: &[u8; 32], msg: &[u8]) -> Option<()> {
+    zip215_equation_holds(&decode_zip215(sig, pk, msg)?).then_some(())
+}
+
+/// A signature decoded following the ZIP-215 rules, with its challenge.
+struct Zip215Signature {
+    /// R component of the signature
+    r: EdwardsPoint,
+    /// s component of the signature
+    s: Scalar,
+    /// public key
+    a: EdwardsPoint,
+    /// RFC 8032 challenge
+    k: Scalar,
+}
+
+/// Decodes a signature following the ZIP-215 rules, returning `None` if the public key or `R`
+/// does not decompress or `s` is not canonical.
+fn decode_zip215(sig: &[u8; 64], pk: &[u8; 32], msg: &[u8]) -> Option<Zip215Signature> {
+    let (r_bytes, s_bytes) = sig.split_at(32);
+    // any encoding that decompresses is accepted, canonical or not
+    let a = CompressedEdwardsY(*pk).decompress()?;
+    let r = CompressedEdwardsY::from_slice(r_bytes).ok()?.decompress()?;
+    let s = Option::<Scalar>::from(Scalar::from_canonical_bytes(s_bytes.try_into().unwrap()))?;
+
+    // the challenge hashes the encodings as given, not their canonical forms
+    let k = challenge(&[], r_bytes, pk, msg);
+    Some(Zip215Signature { r, s, a, k })
+}
+
+/// Returns `true` if a decoded signature passes the cofactored equation `[8][s]B = [8]R + [8][k]A`.
+fn zip215_equation_holds(signature: &Zip215Signature) -> bool {
+    let check = EdwardsPoint::vartime_double_scalar_mul_basepoint(
+        &signature.k,
+        &-signature.a,
+        &signature.s,
+    ) - signature.r;
+    check.is_small_order()
+}
+
+/// Derives the coefficient of the equation at `index` in a batch from the hash of the batch, as the
+/// first [`COEFFICIENT_LEN`] bytes of `SHA-512(transcript || index)`, with `index` as a 4-byte
+/// big-endian integer, read as a little-endian scalar.
+fn random_coefficient(transcript: &Output<Sha512>, index: usize) -> Scalar {
+    let hash = Sha512::new()
+        .chain_update(transcript)
+        .chain_update((index as u32).to_be_bytes())
+        .finalize();
+    let mut z = [0; 32];
+    z[..COEFFICIENT_LEN].copy_from_slice(&hash[..COEFFICIENT_LEN]);
+    Scalar::from_bytes_mod_order(z)
+}
+
+/// Computes the RFC 8032 challenge scalar `k = SHA-512(dom || R || A || M) mod l`, where `dom` is
+/// empty for plain Ed25519.
+fn challenge(dom: &[u8], r: &[u8], pk: &[u8; 32], msg: &[u8]) -> Scalar {
+    let hash = Sha512::new()
+        .chain_update(dom)
+        .chain_update(r)
+        .chain_update(pk)
+        .chain_update(msg)
+        .finalize();
+    Scalar::from_bytes_mod_order_wide(&hash.into())
+}
+
+/// Decompresses an edwards25519 point, rejecting encodings that are not canonical.
+pub(crate) fn decompress_canonical(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+    let point = CompressedEdwardsY(*bytes).decompress()?;
+    is_canonical_encoding(bytes).then_some(point)
+}
+
+/// Returns `false` if `bytes` is a non-canonical encoding of an edwards25519 point, which is
+/// cheaper than compressing the point again to compare. Encodings that are not of a point on the
+/// curve may return either.
+fn is_canonical_encoding(bytes: &[u8; 32]) -> bool {
+    // the encoding is canonical if y is reduced, and the sign bit is clear when x is zero, which
+    // is when y is 1 or -1
+    let mut y_bytes = *bytes;
+    y_bytes[31] &= 0x7f;
+    let Some(y) = field::from_bytes(&y_bytes) else {
+        return false;
+    };
+    let x_is_zero = y == FieldElement::ONE || y == -FieldElement::ONE;
+    !x_is_zero || bytes[31] >> 7 == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::{constants::EIGHT_TORSION, MontgomeryPoint};
+    use revm::primitives::hex;
+
+    /// Encodes the input of [`ED25519VERIFY`] for a signature, public key and message.
+    fn verify_input(r: &[u8; 32], s: &[u8; 32], pk: &[u8; 32], msg: &[u8]) -> Bytes {
+        [r.as_slice(), s, pk, msg].concat().into()
+    }
+
+    /// Returns whether [`ed25519_verify`] accepts the input under each mode, in the order strict,
+    /// ZIP-215, legacy.
+    fn verify_modes(input: &Bytes) -> [bool; 3] {
+        [
+            Ed25519VerifyMode::Strict,
+            Ed25519VerifyMode::Zip215,
+            Ed25519VerifyMode::Legacy,
+        ]
+        .map(|mode| ed25519_verify(input, u64::MAX, mode).unwrap().bytes[31] == 1)
+    }
+
+    /// A non-canonical encoding of the identity, with `y = p + 1`.
+    const NON_CANONICAL_IDENTITY: [u8; 32] = {
+        let mut bytes = [0xff; 32];
+        bytes[0] = 0xee;
+        bytes[31] = 0x7f;
+        bytes
+    };
+
+    /// The canonical encoding of the identity, which has small order.
+    const IDENTITY: [u8; 32] = {
+        let mut bytes = [0; 32];
+        bytes[0] = 1;
+        bytes
+    };
+
+    /// A secret scalar and its public key.
+    fn key_pair(seed: u64) -> (Scalar, [u8; 32]) {
+        let secret = Scalar::from(seed);
+        (
+            secret,
+            (secret * ED25519_BASEPOINT_POINT).compress().to_bytes(),
+        )
+    }
+
+    #[test]
+    fn decompress_canonical_matches_round_trip() {
+        // every non-canonical encoding has a y coordinate below 19 once reduced, or x = 0
+        let low = (0..40u8).map(|y| {
+            let mut bytes = [0; 32];
+            bytes[0] = y;
+            bytes
+        });
+        let high = (0..40u8).map(|offset| {
+            let mut bytes = [0xff; 32];
+            bytes[0] = 0xff - offset;
+            bytes[31] = 0x7f;
+            bytes
+        });
+        for bytes in low.chain(high) {
+            for sign in [0, 0x80] {
+                let mut bytes = bytes;
+                bytes[31] |= sign;
+                let round_trip = CompressedEdwardsY(bytes)
+                    .decompress()
+                    .filter(|point| point.compress().to_bytes() == bytes);
+                assert_eq!(decompress_canonical(&bytes), round_trip, "{bytes:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn modes_agree_on_valid_signature() {
+        let msg = b"ed25519 modes";
+        let (a, pk) = key_pair(1_234_567);
+        let (r, r_bytes) = key_pair(7_654_321);
+        let s = r + challenge(&[], &r_bytes, &pk, msg) * a;
+        let input = verify_input(&r_bytes, s.as_bytes(), &pk, msg);
+        assert_eq!(verify_modes(&input), [true, true, true]);
+    }
+
+    #[test]
+    fn only_zip215_accepts_non_canonical_r() {
+        let msg = b"ed25519 modes";
+        let (a, pk) = key_pair(1_234_567);
+        // R is the identity, so [s]B = [k]A holds exactly with s = k * a
+        let s = challenge(&[], &NON_CANONICAL_IDENTITY, &pk, msg) * a;
+        let input = verify_input(&NON_CANONICAL_IDENTITY, s.as_bytes(), &pk, msg);
+        assert_eq!(verify_modes(&input), [false, true, false]);
+    }
+
+    #[test]
+    fn strict_rejects_non_canonical_a() {
+        let msg = b"ed25519 modes";
+        let (r, r_bytes) = key_pair(7_654_321);
+        // A is the identity, so [s]B = R holds with s = r
+        let input = verify_input(&r_bytes, r.as_bytes(), &NON_CANONICAL_IDENTITY, msg);
+        assert_eq!(verify_modes(&input), [false, true, true]);
+    }
+
+    #[test]
+    fn strict_rejects_small_order_a() {
+        let msg = b"ed25519 modes";
+        let (r, r_bytes) = key_pair(7_654_321);
+        let input = verify_input(&r_bytes, r.as_bytes(), &IDENTITY, msg);
+        assert_eq!(verify_modes(&input), [false, true, true]);
+    }
+
+    #[test]
+    fn all_modes_reject_non_canonical_s() {
+        let msg = b"ed25519 modes";
+        let (a, pk) = key_pair(1_234_567);
+        let (r, r_bytes) = key_pair(7_654_321);
+        let s = r + challenge(&[], &r_bytes, &pk, msg) * a;
+        // s + l, which is below 2^256 and reduces to the same scalar
+        let l = hex!("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010");
+        let mut s_plus_l = [0; 32];
+        let mut carry = 0;
+        for i in 0..32 {
+            let sum = s.as_bytes()[i] as u16 + l[i] as u16 + carry;
+            s_plus_l[i] = sum as u8;
+            carry = sum >> 8;
+        }
+        let input = verify_input(&r_bytes, &s_plus_l, &pk, msg);
+        assert_eq!(verify_modes(&input), [false, false, false]);
+    }
+
+    /// RFC 8032 section 7.1 vectors as `(public key, message, signature)`.
+    const ED25519_VECTORS: [(&[u8; 32], &[u8], &[u8; 64]); 4] = [
+        // TEST 1
+        (
+            &hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"),
+            b"",
+            &hex!(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155"
+                "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            ),
+        ),
+        // TEST 2
+        (
+            &hex!("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"),
+            b"\x72",
+            &hex!(
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da"
+                "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+            ),
+        ),
+        // TEST 3
+        (
+            &hex!("fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025"),
+            b"\xaf\x82",
+            &hex!(
+                "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac"
+                "18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"
+            ),
+        ),
+        // TEST 1024
+        (
+            &hex!("278117fc144c72340f67d0f2316e8386ceffbf2b2428c9c51fef7c597f1d426e"),
+            &hex!(
+                "08b8b2b733424243760fe426a4b54908632110a66c2f6591eabd3345e3e4eb98"
+                "fa6e264bf09efe12ee50f8f54e9f77b1e355f6c50544e23fb1433ddf73be84d8"
+                "79de7c0046dc4996d9e773f4bc9efe5738829adb26c81b37c93a1b270b20329d"
+                "658675fc6ea534e0810a4432826bf58c941efb65d57a338bbd2e26640f89ffbc"
+                "1a858efcb8550ee3a5e1998bd177e93a7363c344fe6b199ee5d02e82d522c4fe"
+                "ba15452f80288a821a579116ec6dad2b3b310da903401aa62100ab5d1a36553e"
+                "06203b33890cc9b832f79ef80560ccb9a39ce767967ed628c6ad573cb116dbef"
+                "efd75499da96bd68a8a97b928a8bbc103b6621fcde2beca1231d206be6cd9ec7"
+                "aff6f6c94fcd7204ed3455c68c83f4a41da4af2b74ef5c53f1d8ac70bdcb7ed1"
+                "85ce81bd84359d44254d95629e9855a94a7c1958d1f8ada5d0532ed8a5aa3fb2"
+                "d17ba70eb6248e594e1a2297acbbb39d502f1a8c6eb6f1ce22b3de1a1f40cc24"
+                "554119a831a9aad6079cad88425de6bde1a9187ebb6092cf67bf2b13fd65f270"
+                "88d78b7e883c8759d2c4f5c65adb7553878ad575f9fad878e80a0c9ba63bcbcc"
+                "2732e69485bbc9c90bfbd62481d9089beccf80cfe2df16a2cf65bd92dd597b07"
+                "07e0917af48bbb75fed413d238f5555a7a569d80c3414a8d0859dc65a46128ba"
+                "b27af87a71314f318c782b23ebfe808b82b0ce26401d2e22f04d83d1255dc51a"
+                "ddd3b75a2b1ae0784504df543af8969be3ea7082ff7fc9888c144da2af58429e"
+                "c96031dbcad3dad9af0dcbaaaf268cb8fcffead94f3c7ca495e056a9b47acdb7"
+                "51fb73e666c6c655ade8297297d07ad1ba5e43f1bca32301651339e22904cc8c"
+                "42f58c30c04aafdb038dda0847dd988dcda6f3bfd15c4b4c4525004aa06eeff8"
+                "ca61783aacec57fb3d1f92b0fe2fd1a85f6724517b65e614ad6808d6f6ee34df"
+                "f7310fdc82aebfd904b01e1dc54b2927094b2db68d6f903b68401adebf5a7e08"
+                "d78ff4ef5d63653a65040cf9bfd4aca7984a74d37145986780fc0b16ac451649"
+                "de6188a7dbdf191f64b5fc5e2ab47b57f7f7276cd419c17a3ca8e1b939ae49e4"
+                "88acba6b965610b5480109c8b17b80e1b7b750dfc7598d5d5011fd2dcc5600a3"
+                "2ef5b52a1ecc820e308aa342721aac0943bf6686b64b2579376504ccc493d97e"
+                "6aed3fb0f9cd71a43dd497f01f17c0e2cb3797aa2a2f256656168e6c496afc5f"
+                "b93246f6b1116398a346f1a641f3b041e989f7914f90cc2c7fff357876e506b5"
+                "0d334ba77c225bc307ba537152f3f1610e4eafe595f6d9d90d11faa933a15ef1"
+                "369546868a7f3a45a96768d40fd9d03412c091c6315cf4fde7cb68606937380d"
+                "b2eaaa707b4c4185c32eddcdd306705e4dc1ffc872eeee475a64dfac86aba41c"
+                "0618983f8741c5ef68d3a101e8a3b8cac60c905c15fc910840b94c00a0b9d0"
+            ),
+            &hex!(
+                "0aab4c900501b3e24d7cdf4663326a3a87df5e4843b2cbdb67cbf6e460fec350"
+                "aa5371b1508f9f4528ecea23c436d94b5e8fcd4f681e30a6ac00a9704a188a03"
+            ),
+        ),
+    ];
+
+    #[test]
+    fn verify_accepts_rfc8032_vectors() {
+        for (i, (pk, msg, sig)) in ED25519_VECTORS.into_iter().enumerate() {
+            let (r, s) = sig.split_at(32);
+            let input = verify_input(r.try_into().unwrap(), s.try_into().unwrap(), pk, msg);
+            assert_eq!(verify_modes(&input), [true, true, true], "vector {i}");
+
+            // appending a byte to the message invalidates the signature
+            let tampered = [msg, &[0]].concat();
+            let input = verify_input(r.try_into().unwrap(), s.try_into().unwrap(), pk, &tampered);
+            assert_eq!(verify_modes(&input), [false, false, false], "vector {i}");
+        }
+    }
+
+    #[test]
+    fn verify_charges_per_message_word() {
+        let (pk, _, sig) = ED25519_VECTORS[0];
+        for (msg_len, gas) in [
+            (0, ED25519VERIFY_BASE),
+            (1, ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD),
+            (32, ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD),
+            (33, ED25519VERIFY_BASE + 2 * ED25519VERIFY_PER_WORD),
+        ] {
+            let input: Bytes = [sig.as_slice(), pk, &vec![0; msg_len]].concat().into();
+            let output = ed25519_verify(&input, gas, Ed25519VerifyMode::Strict).unwrap();
+            assert_eq!(output.gas_used, gas, "{msg_len} bytes");
+            assert!(matches!(
+                ed25519_verify(&input, gas - 1, Ed25519VerifyMode::Strict),
+                Err(PrecompileErrors::Error(PrecompileError::OutOfGas))
+            ));
+        }
+        assert_eq!(ED25519VERIFY_BASE, 3_450);
+        assert_eq!(ED25519VERIFY_PER_WORD, 12);
+    }
+
+    /// Returns the status that [`ED25519PUBKEYVALIDATE`] reports for `pk`.
+    fn key_status(pk: &[u8; 32]) -> u8 {
+        let output = ed25519_pubkey_validate(&pk.to_vec().into(), u64::MAX).unwrap();
+        assert_eq!(output.gas_used, ED25519PUBKEYVALIDATE_GAS);
+        output.bytes[31]
+    }
+
+    #[test]
+    fn validate_reports_valid_key() {
+        let (_, pk) = key_pair(1_234_567);
+        assert_eq!(validate_public_key(&pk), Ed25519KeyStatus::Valid);
+        assert_eq!(key_status(&pk), Ed25519KeyStatus::Valid as u8);
+    }
+
+    #[test]
+    fn validate_reports_key_not_on_curve() {
+        // y = 2 has no matching x coordinate
+        let mut pk = [0; 32];
+        pk[0] = 2;
+        assert!(CompressedEdwardsY(pk).decompress().is_none());
+        assert_eq!(validate_public_key(&pk), Ed25519KeyStatus::NotOnCurve);
+        assert_eq!(key_status(&pk), Ed25519KeyStatus::NotOnCurve as u8);
+    }
+
+    #[test]
+    fn validate_reports_non_canonical_key() {
+        // both encodings are of the identity, but the encoding is checked before the order
+        let mut negative_zero = IDENTITY;
+        negative_zero[31] |= 0x80;
+        for pk in [NON_CANONICAL_IDENTITY, negative_zero] {
+            assert_eq!(validate_public_key(&pk), Ed25519KeyStatus::NonCanonical);
+            assert_eq!(key_status(&pk), Ed25519KeyStatus::NonCanonical as u8);
+        }
+    }
+
+    #[test]
+    fn validate_reports_small_order_key() {
+        for point in EIGHT_TORSION {
+            let pk = point.compress().to_bytes();
+            assert_eq!(validate_public_key(&pk), Ed25519KeyStatus::SmallOrder);
+            assert_eq!(key_status(&pk), Ed25519KeyStatus::SmallOrder as u8);
+        }
+    }
+
+    #[test]
+    fn validate_reports_mixed_order_key() {
+        let (_, pk) = mixed_order_key_pair();
+        assert_eq!(validate_public_key(&pk), Ed25519KeyStatus::MixedOrder);
+        assert_eq!(key_status(&pk), Ed25519KeyStatus::MixedOrder as u8);
+    }
+
+    #[test]
+    fn validate_rejects_wrong_length() {
+        let (_, pk) = key_pair(1_234_567);
+        for input in [&pk[..31], &[pk.as_slice(), &[0]].concat()] {
+            assert!(ed25519_pubkey_validate(&input.to_vec().into(), u64::MAX).is_err());
+        }
+    }
+
+    #[test]
+    fn keccak_verifies_signed_digest() {
+        let hash = keccak256(b"authorized call");
+        let digest = keccak256([ETH_SIGNED_MESSAGE_PREFIX, hash.as_slice()].concat());
+        let (a, pk) = key_pair(1_234_567);
+        let sig = sign(a, &pk, 7_654_321, 0, digest.as_slice());
+        let input = [sig.as_slice(), &pk, hash.as_slice()].concat();
+        let output = ed25519_keccak_verify(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        assert_eq!(
+            ED25519KECCAKVERIFY_GAS,
+            ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD + 30 + 2 * 6
+        );
+        assert_eq!(output.gas_used, ED25519KECCAKVERIFY_GAS);
+
+        // the signature is over the digest, not the hash itself
+        let sig = sign(a, &pk, 7_654_321, 0, hash.as_slice());
+        let input = [sig.as_slice(), &pk, hash.as_slice()].concat();
+        assert!(keccak_verify_impl(&input).is_none());
+    }
+
+    #[test]
+    fn keccak_rejects_wrong_length() {
+        let hash = keccak256(b"authorized call");
+        let digest = keccak256([ETH_SIGNED_MESSAGE_PREFIX, hash.as_slice()].concat());
+        let (a, pk) = key_pair(1_234_567);
+        let sig = sign(a, &pk, 7_654_321, 0, digest.as_slice());
+        let input = [sig.as_slice(), &pk, hash.as_slice()].concat();
+        for input in [&input[..127], &[input.as_slice(), &[0]].concat()] {
+            let output = ed25519_keccak_verify(&input.to_vec().into(), u64::MAX).unwrap();
+            assert_eq!(output.bytes[31], 0);
+            assert_eq!(output.gas_used, ED25519KECCAKVERIFY_GAS);
+        }
+    }
+
+    /// Returns the output of [`ED25519TOX25519`] for `input`.
+    fn to_x25519(input: &[u8]) -> Bytes {
+        let output = ed25519_to_x25519(&input.to_vec().into(), u64::MAX).unwrap();
+        assert_eq!(output.gas_used, ED25519TOX25519_GAS);
+        output.bytes
+    }
+
+    #[test]
+    fn to_x25519_matches_libsodium() {
+        // the key pair of libsodium's `ed25519_convert` test
+        let seed = hex!("421151a459faeade3d247115f94aedae42318124095afabe4d1451a559faedee");
+        let pk = ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key();
+        assert_eq!(
+            to_x25519(pk.as_bytes())[..],
+            hex!("f1814f0e8ff1043d8a44d25babff3cedcae6c22c3edaa48f857ae70de2baae50")
+        );
+    }
+
+    #[test]
+    fn to_x25519_matches_montgomery_map() {
+        for seed in 0..8 {
+            let (a, pk) = key_pair(16_000 + seed);
+            let u = VerifyingKey::from_bytes(&pk).unwrap().to_montgomery();
+            assert_eq!(to_x25519(&pk)[..], u.to_bytes());
+            // which is the X25519 public key of the same secret scalar
+            assert_eq!(u, MontgomeryPoint::mul_base(&a));
+        }
+    }
+
+    #[test]
+    fn to_x25519_rejects_invalid_keys() {
+        for point in EIGHT_TORSION {
+            assert!(to_x25519(&point.compress().to_bytes()).is_empty());
+        }
+        assert!(to_x25519(&NON_CANONICAL_IDENTITY).is_empty());
+
+        // y = p + k for the y = k that are on the curve and not of small order
+        let non_canonical: Vec<_> = (2..19u8)
+            .map(|k| {
+                let mut bytes = [0xff; 32];
+                bytes[0] = 0xed + k;
+                bytes[31] = 0x7f;
+                bytes
+            })
+            .filter(|bytes| {
+                CompressedEdwardsY(*bytes)
+                    .decompress()
+                    .is_some_and(|point| !point.is_small_order())
+            })
+            .collect();
+        assert!(!non_canonical.is_empty());
+        for pk in non_canonical {
+            assert!(to_x25519(&pk).is_empty());
+        }
+
+        let (_, pk) = key_pair(16_000);
+        assert!(to_x25519(&pk[..31]).is_empty());
+        assert!(to_x25519(&[pk.as_slice(), &[0]].concat()).is_empty());
+    }
+
+    /// RFC 8032 vectors as `(context, public key, message, signature)`.
+    type CtxVector = (&'static [u8], [u8; 32], [u8; 16], [u8; 64]);
+
+    /// RFC 8032 section 7.2 vectors.
+    const ED25519CTX_VECTORS: [CtxVector; 4] = [
+        (
+            b"foo",
+            hex!("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292"),
+            hex!("f726936d19c800494e3fdaff20b276a8"),
+            hex!(
+                "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a"
+                "8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d"
+            ),
+        ),
+        (
+            b"bar",
+            hex!("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292"),
+            hex!("f726936d19c800494e3fdaff20b276a8"),
+            hex!(
+                "fc60d5872fc46b3aa69f8b5b4351d5808f92bcc044606db097abab6dbcb1aee3"
+                "216c48e8b3b66431b5b186d1d28f8ee15a5ca2df6668346291c2043d4eb3e90d"
+            ),
+        ),
+        (
+            b"foo",
+            hex!("dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292"),
+            hex!("508e9e6882b979fea900f62adceaca35"),
+            hex!(
+                "8b70c1cc8310e1de20ac53ce28ae6e7207f33c3295e03bb5c0732a1d20dc6490"
+                "8922a8b052cf99b7c4fe107a5abb5b2c4085ae75890d02df26269d8945f84b0b"
+            ),
+        ),
+        (
+            b"foo",
+            hex!("0f1d1274943b91415889152e893d80e93275a1fc0b65fd71b4b0dda10ad7d772"),
+            hex!("f726936d19c800494e3fdaff20b276a8"),
+            hex!(
+                "21655b5f1aa965996b3f97b3c849eafba922a0a62992f73b3d1b73106a84ad85"
+                "e9b86a7b6005ea868337ff2d20a7f5fbd4cd10b0be49a68da2b2e0dc0ad8960f"
+            ),
+        ),
+    ];
+
+    /// Encodes the input of [`ED25519CTXVERIFY`] for a context, signature, public key and message.
+    fn ctx_input(context: &[u8], sig: &[u8; 64], pk: &[u8; 32], msg: &[u8]) -> Vec<u8> {
+        [&[context.len() as u8], context, sig, pk, msg].concat()
+    }
+
+    #[test]
+    fn ctx_accepts_rfc8032_vectors() {
+        for (i, (context, pk, msg, sig)) in ED25519CTX_VECTORS.iter().enumerate() {
+            let input = ctx_input(context, sig, pk, msg);
+            assert!(ed25519ctx_verify_impl(&input).is_some(), "vector {i}");
+            // the context is bound to the signature, so the `foo` signatures fail under `bar`
+            let other: &[u8] = if *context == b"bar" { b"foo" } else { b"bar" };
+            let input = ctx_input(other, sig, pk, msg);
+            assert!(ed25519ctx_verify_impl(&input).is_none(), "vector {i}");
+        }
+
+        let (context, pk, msg, sig) = ED25519CTX_VECTORS[0];
+        let output =
+            ed25519ctx_verify(&ctx_input(context, &sig, &pk, &msg).into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        // the context and message make up one word
+        assert_eq!(output.gas_used, ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD);
+    }
+
+    #[test]
+    fn ctx_rejects_empty_context() {
+        let msg = b"ed25519ctx";
+        let (a, pk) = key_pair(1_234_567);
+        let (r, r_bytes) = key_pair(7_654_321);
+        // a valid signature for the empty context, which RFC 8032 does not allow for Ed25519ctx
+        let dom2 = [DOM2_PREFIX, &[0, 0]].concat();
+        let s = r + challenge(&dom2, &r_bytes, &pk, msg) * a;
+        let sig = [r_bytes, s.to_bytes()].concat().try_into().unwrap();
+        let input = ctx_input(&[], &sig, &pk, msg);
+        assert!(ed25519ctx_verify_impl(&input).is_none());
+        let output = ed25519ctx_verify(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[..], [0; 32]);
+    }
+
+    /// Encodes the input of [`ED25519PHVERIFY`] for the RFC 8032 section 7.3 vector, followed by
+    /// `context`.
+    fn ph_input(context: &[u8]) -> Vec<u8> {
+        let digest = Sha512::digest(b"abc");
+        let sig = hex!(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae41"
+            "31f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406"
+        );
+        let pk = hex!("ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf");
+        [digest.as_slice(), &sig, &pk, context].concat()
+    }
+
+    #[test]
+    fn prehash_returns_the_digest() {
+        let digest: [u8; 64] = Sha512::digest(b"abc").into();
+        assert_eq!(Sha512Prehash::from(&digest).finalize(), digest.into());
+    }
+
+    #[test]
+    fn ph_accepts_rfc8032_vector() {
+        let output = ed25519ph_verify(&ph_input(&[]).into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        assert_eq!(output.gas_used, ED25519VERIFY_BASE);
+
+        // an explicit zero context length is the same empty context
+        let output = ed25519ph_verify(&ph_input(&[0]).into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        assert_eq!(output.gas_used, ED25519VERIFY_BASE);
+
+        // the signature is bound to the empty context
+        assert!(ed25519ph_verify_impl(&ph_input(&[3, b'f', b'o', b'o'])).is_none());
+    }
+
+    #[test]
+    fn ph_rejects_mismatched_context_length() {
+        // the prefix claims more context bytes than follow it, or fewer
+        assert!(ed25519ph_verify_impl(&ph_input(&[2, b'f'])).is_none());
+        assert!(ed25519ph_verify_impl(&ph_input(&[0, 0])).is_none());
+        assert!(ed25519ph_verify_impl(&ph_input(&[1, b'f', b'o'])).is_none());
+    }
+
+    /// Signs `msg` with the cofactorless equation, for a public key that need not be `[a]B`. The
+    /// `R` component of the signature is offset by `EIGHT_TORSION[torsion]`.
+    fn sign(a: Scalar, pk: &[u8; 32], nonce: u64, torsion: usize, msg: &[u8]) -> [u8; 64] {
+        let r = Scalar::from(nonce);
+        let r_bytes = (r * ED25519_BASEPOINT_POINT + EIGHT_TORSION[torsion])
+            .compress()
+            .to_bytes();
+        let s = r + challenge(&[], &r_bytes, pk, msg) * a;
+        let mut sig = [0; 64];
+        sig[..32].copy_from_slice(&r_bytes);
+        sig[32..].copy_from_slice(s.as_bytes());
+        sig
+    }
+
+    /// Encodes the input of [`ED25519BATCHVERIFY`] for `(signature, public key, message)` entries.
+    fn batch_input(entries: &[([u8; 64], [u8; 32], &[u8])]) -> Bytes {
+        let mut input = Vec::new();
+        for (sig, pk, msg) in entries {
+            input.extend_from_slice(sig);
+            input.extend_from_slice(pk);
+            input.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+            input.extend_from_slice(msg);
+        }
+        input.into()
+    }
+
+    /// A key pair whose public key has a torsion component of order 8.
+    fn mixed_order_key_pair() -> (Scalar, [u8; 32]) {
+        let a = Scalar::from(424_242u64);
+        let point = a * ED25519_BASEPOINT_POINT + EIGHT_TORSION[1];
+        (a, point.compress().to_bytes())
+    }
+
+    #[test]
+    fn batch_accepts_valid_signatures() {
+        let entries: Vec<_> = (0..5u64)
+            .map(|i| {
+                let (a, pk) = key_pair(1_000 + i);
+                (
+                    sign(a, &pk, 2_000 + i, 0, b"batch"),
+                    pk,
+                    b"batch".as_slice(),
+                )
+            })
+            .collect();
+        let output = ed25519_batch_verify(&batch_input(&entries), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+    }
+
+    /// Returns whether [`ED25519BATCHVERIFY`] accepts a valid signature together with `entry`.
+    fn batch_accepts(entry: ([u8; 64], [u8; 32], &[u8])) -> bool {
+        let (a, pk) = key_pair(1_234_567);
+        let valid = (sign(a, &pk, 4_000, 0, b"batch"), pk, b"batch".as_slice());
+        let output = ed25519_batch_verify(&batch_input(&[valid, entry]), u64::MAX).unwrap();
+        output.bytes[31] == 1
+    }
+
+    #[test]
+    fn batch_agrees_with_zip215() {
+        let (mixed_a, mixed_pk) = mixed_order_key_pair();
+        let mut strict_rejected = 0;
+        for i in 0..64u64 {
+            let msg = i.to_be_bytes();
+            // the torsion components of `R` and `[k]A` only cancel out for some values of k mod 8,
+            // which the cofactored equation does not depend on
+            let mixed_sig = sign(mixed_a, &mixed_pk, 3_000 + i, i as usize % 8, &msg);
+            let forged_sig = sign(mixed_a + Scalar::ONE, &mixed_pk, 3_000 + i, 0, &msg);
+            for sig in [mixed_sig, forged_sig] {
+                let zip215 = verify_signature(&sig, &mixed_pk, &msg, Ed25519VerifyMode::Zip215);
+                assert_eq!(
+                    batch_accepts((sig, mixed_pk, &msg)),
+                    zip215.is_some(),
+                    "{i}"
+                );
+            }
+            let strict = verify_signature(&mixed_sig, &mixed_pk, &msg, Ed25519VerifyMode::Strict);
+            strict_rejected += strict.is_none() as usize;
+        }
+        // the batch accepts signatures that strict verification rejects
+        assert!(strict_rejected > 0);
+
+        // as do the encodings that only ZIP-215 accepts
+        let msg = b"ed25519 modes";
+        let (a, pk) = key_pair(1_234_567);
+        let s = challenge(&[], &NON_CANONICAL_IDENTITY, &pk, msg) * a;
+        let sig = [NON_CANONICAL_IDENTITY, *s.as_bytes()].concat();
+        assert!(batch_accepts((sig.try_into().unwrap(), pk, msg)));
+        let (r, r_bytes) = key_pair(7_654_321);
+        let sig = [r_bytes, *r.as_bytes()].concat();
+        assert!(batch_accepts((
+            sig.try_into().unwrap(),
+            NON_CANONICAL_IDENTITY,
+            msg
+        )));
+    }
+
+    #[test]
+    fn bitmap_matches_zip215_verification() {
+        let (mixed_a, mixed_pk) = mixed_order_key_pair();
+        let msg = b"bitmap".as_slice();
+        let mut entries: Vec<_> = (0..12u64)
+            .map(|i| {
+                let (a, pk) = key_pair(5_000 + i);
+                (sign(a, &pk, 6_000 + i, 0, msg), pk, msg)
+            })
+            .collect();
+        let fast_path_gas = ED25519BATCHVERIFY_BASE
+            + 12 * (ED25519BATCHVERIFY_PER_SIGNATURE + ED25519VERIFY_PER_WORD);
+        let check = |entries: &[([u8; 64], [u8; 32], &[u8])], gas| {
+            let output = ed25519_batch_bitmap(&batch_input(entries), u64::MAX).unwrap();
+            for (i, (sig, pk, msg)) in entries.iter().enumerate() {
+                let zip215 = verify_signature(sig, pk, msg, Ed25519VerifyMode::Zip215).is_some();
+                assert_eq!(output.bytes[i / 8] >> (i % 8) & 1 == 1, zip215, "entry {i}");
+            }
+            assert_eq!(output.gas_used, gas);
+            output.bytes
+        };
+
+        // a batch of valid signatures takes the fast path
+        assert_eq!(check(&entries, fast_path_gas).as_ref(), [0xff, 0x0f]);
+
+        // so does one with mixed-order signatures, which are valid up to torsion, and with a
+        // public key that does not decode, which is left out of the batch
+        for i in [5, 6, 7, 10] {
+            entries[i] = (
+                sign(mixed_a, &mixed_pk, 7_000 + i as u64, i % 8, msg),
+                mixed_pk,
+                msg,
+            );
+        }
+        let not_on_curve = (0u8..)
+            .map(|y| [y; 32])
+            .find(|bytes| CompressedEdwardsY(*bytes).decompress().is_none())
+            .unwrap();
+        entries[8].1 = not_on_curve;
+        assert_eq!(check(&entries, fast_path_gas).as_ref(), [0xff, 0x0e]);
+
+        // an invalid signature makes the decoded entries fall back to single checks
+        let (a, pk) = key_pair(5_003);
+        entries[3].0 = sign(a, &pk, 6_003, 0, b"other");
+        let fallback_gas = fast_path_gas + 11 * ED25519BATCHBITMAP_PER_FALLBACK;
+        assert_eq!(check(&entries, fallback_gas).as_ref(), [0xf7, 0x0e]);
+    }
+
+    /// Encodes the input of [`ED25519HALFAGGVERIFY`] for `(signature, public key, message)`
+    /// entries, aggregating their `s` components.
+    fn halfagg_input(entries: &[([u8; 64], [u8; 32], &[u8])]) -> Bytes {
+        let mut encoded = Vec::new();
+        for (sig, pk, msg) in entries {
+            encoded.extend_from_slice(&sig[..32]);
+            encoded.extend_from_slice(pk);
+            encoded.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+            encoded.extend_from_slice(msg);
+        }
+        let transcript = Sha512::new()
+            .chain_update(HALFAGG_DOMAIN)
+            .chain_update(&encoded)
+            .finalize();
+        let s: Scalar = entries
+            .iter()
+            .enumerate()
+            .map(|(i, (sig, _, _))| {
+                let s = Scalar::from_canonical_bytes(sig[32..].try_into().unwrap()).unwrap();
+                random_coefficient(&transcript, i) * s
+            })
+            .sum();
+        [s.as_bytes().as_slice(), &encoded].concat().into()
+    }
+
+    #[test]
+    fn halfagg_accepts_valid_signatures() {
+        let entries: Vec<_> = (0..5u64)
+            .map(|i| {
+                let (a, pk) = key_pair(8_000 + i);
+                (
+                    sign(a, &pk, 9_000 + i, 0, b"halfagg"),
+                    pk,
+                    b"halfagg".as_slice(),
+                )
+            })
+            .collect();
+        let output = ed25519_halfagg_verify(&halfagg_input(&entries), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+
+        // the aggregate does not verify for other messages
+        let mut entries = entries;
+        entries[2].2 = b"other";
+        let output = ed25519_halfagg_verify(&halfagg_input(&entries), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+    }
+
+    #[test]
+    fn halfagg_agrees_with_zip215() {
+        let (a, pk) = key_pair(7_654_321);
+        let (mixed_a, mixed_pk) = mixed_order_key_pair();
+        let mut strict_rejected = 0;
+        for i in 0..64u64 {
+            let msg = i.to_be_bytes();
+            let mixed_sig = sign(mixed_a, &mixed_pk, 10_000 + i, i as usize % 8, &msg);
+            let forged_sig = sign(mixed_a + Scalar::ONE, &mixed_pk, 10_000 + i, 0, &msg);
+            for sig in [mixed_sig, forged_sig] {
+                let zip215 = verify_signature(&sig, &mixed_pk, &msg, Ed25519VerifyMode::Zip215);
+                let entries = [
+                    (sign(a, &pk, 11_000 + i, 0, &msg), pk, msg.as_slice()),
+                    (sig, mixed_pk, msg.as_slice()),
+                ];
+                let output = ed25519_halfagg_verify(&halfagg_input(&entries), u64::MAX).unwrap();
+                assert_eq!(output.bytes[31] == 1, zip215.is_some(), "message {i}");
+            }
+            let strict = verify_signature(&mixed_sig, &mixed_pk, &msg, Ed25519VerifyMode::Strict);
+            strict_rejected += strict.is_none() as usize;
+        }
+        // the aggregate accepts signatures that strict verification rejects
+        assert!(strict_rejected > 0);
+    }
+
+    #[test]
+    fn halfagg_costs_less_than_separate_verification() {
+        let msg = b"halfagg".as_slice();
+        let entries: Vec<_> = (0..2u64)
+            .map(|i| {
+                let (a, pk) = key_pair(14_000 + i);
+                (sign(a, &pk, 15_000 + i, 0, msg), pk, msg)
+            })
+            .collect();
+        let output = ed25519_halfagg_verify(&halfagg_input(&entries), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 1);
+        // each entry is 32 + 32 + 4 + 7 = 75 bytes, so the transcript has 5 words
+        let gas = ED25519BATCHVERIFY_BASE
+            + 5 * ED25519VERIFY_PER_WORD
+            + 2 * (ED25519BATCHVERIFY_PER_SIGNATURE + ED25519VERIFY_PER_WORD);
+        assert_eq!(output.gas_used, gas);
+        assert!(gas < 2 * (ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD));
+    }
+
+    /// Encodes the input of [`ED25519THRESHOLDVERIFY`] for weighted keys and indexed signatures.
+    fn threshold_input(
+        threshold: u64,
+        keys: &[([u8; 32], u64)],
+        signatures: &[(u8, [u8; 64])],
+        msg: &[u8],
+    ) -> Bytes {
+        let mut input = threshold.to_be_bytes().to_vec();
+        input.push(keys.len() as u8);
+        for (pk, weight) in keys {
+            input.extend_from_slice(pk);
+            input.extend_from_slice(&weight.to_be_bytes());
+        }
+        input.push(signatures.len() as u8);
+        for (index, sig) in signatures {
+            input.push(*index);
+            input.extend_from_slice(sig);
+        }
+        input.extend_from_slice(msg);
+        input.into()
+    }
+
+    #[test]
+    fn threshold_counts_weight_of_signers() {
+        let msg = b"multisig";
+        let signers: Vec<_> = (0..3u64).map(|i| key_pair(12_000 + i)).collect();
+        let keys: Vec<_> = signers.iter().map(|&(_, pk)| (pk, 1)).collect();
+        let sig = |i: usize| (i as u8, sign(signers[i].0, &signers[i].1, 13_000, 0, msg));
+
+        let output =
+            ed25519_threshold_verify(&threshold_input(2, &keys, &[sig(0), sig(2)], msg), u64::MAX)
+                .unwrap();
+        assert_eq!(output.bytes[31], 1);
+        let gas = ED25519BATCHVERIFY_BASE
+            + 2 * (ED25519BATCHVERIFY_PER_SIGNATURE + ED25519VERIFY_PER_WORD)
+            + 3 * ED25519THRESHOLDVERIFY_PER_KEY;
+        assert_eq!(output.gas_used, gas);
+        // which is less than verifying the two signatures separately
+        assert!(gas < 2 * (ED25519VERIFY_BASE + ED25519VERIFY_PER_WORD));
+
+        // a single signer does not meet the threshold
+        let output =
+            ed25519_threshold_verify(&threshold_input(2, &keys, &[sig(1)], msg), u64::MAX).unwrap();
+        assert_eq!(output.bytes[31], 0);
+
+        // nor does signing twice under the same index
+        let output =
+            ed25519_threshold_verify(&threshold_input(2, &keys, &[sig(1), sig(1)], msg), u64::MAX)
+                .unwrap();
+        assert_eq!(output.bytes[31], 0);
+        assert_eq!(output.gas_used, ED25519BATCHVERIFY_BASE);
+    }
+
+    #[test]
+    fn threshold_rejects_repeated_public_keys() {
+        let msg = b"multisig";
+        let (a, pk) = key_pair(14_000);
+        let (_, other_pk) = key_pair(14_001);
+        let keys = [(pk, 1), (other_pk, 1), (pk, 1)];
+        let signatures = [
+            (0, sign(a, &pk, 15_000, 0, msg)),
+            (2, sign(a, &pk, 15_001, 0, msg)),
+        ];
+        let output =
+            ed25519_threshold_verify(&threshold_input(2, &keys, &signatures, msg), u64::MAX)
+                .unwrap();
+        assert_eq!(output.bytes[31], 0);
+        assert_eq!(output.gas_used, ED25519BATCHVERIFY_BASE);
+    }
+
+    #[test]
+    fn threshold_rejects_non_canonical_public_keys() {
+        let msg = b"multisig";
+        // anyone can sign for the identity, with s = r
+        let (r, r_bytes) = key_pair(16_000);
+        let sig: [u8; 64] = [r_bytes, *r.as_bytes()].concat().try_into().unwrap();
+        let output = ed25519_threshold_verify(
+            &threshold_input(1, &[(IDENTITY, 1)], &[(0, sig)], msg),
+            u64::MAX,
+        )
+        .unwrap();
+        assert_eq!(output.bytes[31], 1);
+
+        // but not count twice for it under another encoding
+        let keys = [(IDENTITY, 1), (NON_CANONICAL_IDENTITY, 1)];
+        let signatures = [(0, sig), (1, sig)];
+        let output =
+            ed25519_threshold_verify(&threshold_input(2, &keys, &signatures, msg), u64::MAX)
+                .unwrap();
+        assert_eq!(output.bytes[31], 0);
+        assert_eq!(output.gas_used, ED25519BATCHVERIFY_BASE);
+    }
+
+    /// Hash functions of Merkle trees and their selectors.
+    const MERKLE_HASHES: [(u8, MerkleHash); 3] = [
+        (MERKLE_SHA256, |data| Sha256::digest(data).into()),
+        (MERKLE_KECCAK256, |data| keccak256(data).0),
+        (MERKLE_SHA512_256, |data| Sha512_256::digest(data).into()),
+    ];
+
+    /// Encodes the input of [`ED25519MERKLEVERIFY`].
+    fn merkle_input(
+        hash: u8,
+        sig: &[u8; 64],
+        pk: &[u8; 32],
+        index: u64,
+        siblings: &[[u8; 32]],
+        leaf: &[u8],
+    ) -> Bytes {
+        let mut input = vec![hash];
+        input.extend_from_slice(sig);
+        input.extend_from_slice(pk);
+        input.extend_from_slice(&index.to_be_bytes());
+        input.push(siblings.len() as u8);
+        input.extend_from_slice(siblings.as_flattened());
+        input.extend_from_slice(leaf);
+        input.into()
+    }
+
+    #[test]
+    fn merkle_verifies_rfc6962_tree() {
+        let (a, pk) = key_pair(16_000);
+        let leaves: [&[u8]; 4] = [b"zero", b"one", b"two", b"three"];
+        for (selector, hash) in MERKLE_HASHES {
+            let leaf_hashes = leaves.map(|leaf| hash(&[&[0x00], leaf].concat()));
+            let node = |left: &[u8; 32], right: &[u8; 32]| {
+                hash(&[&[0x01], left.as_slice(), right].concat())
+            };
+            let inner = [
+                node(&leaf_hashes[0], &leaf_hashes[1]),
+                node(&leaf_hashes[2], &leaf_hashes[3]),
+            ];
+            let root = node(&inner[0], &inner[1]);
+            let sig = sign(a, &pk, 17_000, 0, &root);
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                let siblings = [leaf_hashes[index ^ 1], inner[1 - index / 2]];
+                let input = merkle_input(selector, &sig, &pk, index as u64, &siblings, leaf);
+                let output = ed25519_merkle_verify(&input, u64::MAX).unwrap();
+                assert_eq!(output.bytes[31], 1, "hash {selector}, leaf {index}");
+            }
+
+            // the pair of nodes under the root does not pass as a leaf of a shorter proof
+            let leaf = inner.as_flattened();
+            let output =
+                ed25519_merkle_verify(&merkle_input(selector, &sig, &pk, 0, &[], leaf), u64::MAX)
+                    .unwrap();
+            assert_eq!(output.bytes[31], 0, "hash {selector}");
+            let leaf = leaf_hashes[..2].as_flattened();
+            let input = merkle_input(selector, &sig, &pk, 0, &inner[1..], leaf);
+            let output = ed25519_merkle_verify(&input, u64::MAX).unwrap();
+            assert_eq!(output.bytes[31], 0, "hash {selector}");
+        }
+    }
+
+    /// Encodes the input of [`ED25519VERIFY_EIP665`] for a message signed with the secret key of
+    /// RFC 8032 TEST 1.
+    fn eip665_input(msg: &[u8; 32]) -> Vec<u8> {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&revm::primitives::hex!(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"
+        ));
+        let pk = revm::primitives::hex!(
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"
+        );
+        assert_eq!(signing_key.verifying_key().to_bytes(), pk);
+        let sig = ed25519_dalek::Signer::sign(&signing_key, msg);
+        [msg.as_slice(), &pk, &sig.to_bytes()].concat()
+    }
+
+    #[test]
+    fn eip665_outputs_zero_on_success() {
+        let input = eip665_input(&[0x42; 32]);
+        let output = ed25519_verify_eip665(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes.as_ref(), [0; 4]);
+        assert_eq!(output.gas_used, ED25519VERIFY_EIP665_GAS);
+    }
+
+    #[test]
+    fn eip665_outputs_non_zero_on_failure() {
+        let mut input = eip665_input(&[0x42; 32]);
+        input[0] ^= 1;
+        let output = ed25519_verify_eip665(&input.into(), u64::MAX).unwrap();
+        assert_eq!(output.bytes.as_ref(), [0xff; 4]);
+        assert_eq!(output.gas_used, ED25519VERIFY_EIP665_GAS);
+    }
+
+    #[test]
+    fn eip665_rejects_other_input_lengths() {
+        let input = eip665_input(&[0x42; 32]);
+        for input in [&input[..127], &[input.as_slice(), &[0]].concat(), &[]] {
+            let input = Bytes::copy_from_slice(input);
+            assert!(ed25519_verify_eip665(&input, u64::MAX).is_err());
+        }
+    }
 }
@@ -0,0 +1,286 @@
+//! # edwards25519 Precompile
+//!
+//! This module implements a precompile for raw edwards25519 point arithmetic, the curve that
+//! ed25519 signatures are defined over, so that contracts can build their own EdDSA variants and
+//! key-blinding schemes.
+//!
+//! The [`EDWARDS25519`] const represents the implementation of this precompile, with the address
+//! that it is currently deployed at. The first byte of the input selects the operation.
+//!
+//! [`EDWARDS25519_MSM`] is a separate precompile that computes `sum(s_i * P_i)` over edwards25519
+//! points in one call, as a signature scheme built on [`EDWARDS25519`] needs for its verification
+//! equation, and charges less per pair the more pairs it is given.
+
+use crate::{
+    addresses::{EDWARDS25519_ADDRESS, EDWARDS25519_MSM_ADDRESS},
+    ed25519::decompress_canonical,
+    field::{self, FieldElement, EDWARDS_D},
+    utils::{decode_scalar, msm_pair_count, msm_required_gas, split_args, MSM_PAIR_LEN},
+};
+use curve25519_dalek::{traits::VartimeMultiscalarMul, EdwardsPoint};
+use revm::{
+    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult},
+};
+
+/// Name of the precompile, used in error messages.
+const NAME: &str = "edwards25519";
+
+/// Selector of the decompression operation.
+const EDWARDS25519_DECOMPRESS: u8 = 0x00;
+/// Selector of the point addition operation.
+const EDWARDS25519_ADD: u8 = 0x01;
+/// Selector of the point negation operation.
+const EDWARDS25519_NEG: u8 = 0x02;
+/// Selector of the scalar multiplication operation.
+const EDWARDS25519_MUL: u8 = 0x03;
+/// Selector of the basepoint multiplication operation.
+const EDWARDS25519_MUL_BASE: u8 = 0x04;
+/// Selector of the cofactor clearing operation.
+const EDWARDS25519_CLEAR_COFACTOR: u8 = 0x05;
+
+/// Gas fee for the decompression operation.
+const EDWARDS25519_DECOMPRESS_GAS: u64 = 600;
+/// Gas fee for the point addition operation.
+const EDWARDS25519_ADD_GAS: u64 = 700;
+/// Gas fee for the point negation operation.
+const EDWARDS25519_NEG_GAS: u64 = 400;
+/// Gas fee for the scalar multiplication operation.
+const EDWARDS25519_MUL_GAS: u64 = 2_500;
+/// Gas fee for the basepoint multiplication operation.
+const EDWARDS25519_MUL_BASE_GAS: u64 = 1_200;
+/// Gas fee for the cofactor clearing operation.
+const EDWARDS25519_CLEAR_COFACTOR_GAS: u64 = 500;
+
+/// Returns the edwards25519 precompiles with their addresses.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [EDWARDS25519, EDWARDS25519_MSM].into_iter()
+}
+
+/// edwards25519 precompile.
+pub const EDWARDS25519: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(EDWARDS25519_ADDRESS),
+    Precompile::Standard(edwards25519_run),
+);
+
+/// edwards25519 multi-scalar multiplication precompile.
+pub const EDWARDS25519_MSM: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(EDWARDS25519_MSM_ADDRESS),
+    Precompile::Standard(edwards25519_msm),
+);
+
+/// edwards25519 precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit, and runs the operation selected by the first byte of the input on the arguments that
+/// follow it.
+///
+/// Points are 32-byte compressed edwards25519 encodings, as used by ed25519 public keys, and
+/// scalars are 32-byte little-endian integers below the order of the basepoint. The operations
+/// are:
+///
+/// | selector | operation      | arguments     | output                            | gas   |
+/// | :------: | :------------: | :-----------: | :-------------------------------: | :---: |
+/// |   0x00   | decompress     | point         | affine `x` and `y`, little-endian | 600   |
+/// |   0x01   | add            | point, point  | point                             | 700   |
+/// |   0x02   | neg            | point         | point                             | 400   |
+/// |   0x03   | mul            | scalar, point | point                             | 2,500 |
+/// |   0x04   | mul base       | scalar        | point                             | 1,200 |
+/// |   0x05   | clear cofactor | point         | point multiplied by 8             | 500   |
+///
+/// Points must be canonically encoded and on the curve, but may have small order. Invalid
+/// encodings, unknown selectors and arguments of the wrong length are errors.
+fn edwards25519_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some((&selector, args)) = input.split_first() else {
+        return Err(PrecompileError::other(format!("{NAME}: missing operation selector")).into());
+    };
+    let gas_used = match selector {
+        EDWARDS25519_DECOMPRESS => EDWARDS25519_DECOMPRESS_GAS,
+        EDWARDS25519_ADD => EDWARDS25519_ADD_GAS,
+        EDWARDS25519_NEG => EDWARDS25519_NEG_GAS,
+        EDWARDS25519_MUL => EDWARDS25519_MUL_GAS,
+        EDWARDS25519_MUL_BASE => EDWARDS25519_MUL_BASE_GAS,
+        EDWARDS25519_CLEAR_COFACTOR => EDWARDS25519_CLEAR_COFACTOR_GAS,
+        _ => {
+            return Err(PrecompileError::other(format!(
+                "{NAME}: unknown operation selector {selector:#04x}"
+            ))
+            .into())
+        }
+    };
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let output = match selector {
+        EDWARDS25519_DECOMPRESS => {
+            let [point] = split_args(NAME, args)?;
+            decode_point(point)?;
+            affine_coordinates(point).concat().into()
+        }
+        EDWARDS25519_ADD => {
+            let [a, b] = split_args(NAME, args)?;
+            encode(decode_point(a)? + decode_point(b)?)
+        }
+        EDWARDS25519_NEG => {
+            let [point] = split_args(NAME, args)?;
+            encode(-decode_point(point)?)
+        }
+        EDWARDS25519_MUL => {
+            let [scalar, point] = split_args(NAME, args)?;
+            encode(decode_scalar(NAME, scalar)? * decode_point(point)?)
+        }
+        EDWARDS25519_MUL_BASE => {
+            let [scalar] = split_args(NAME, args)?;
+            encode(EdwardsPoint::mul_base(&decode_scalar(NAME, scalar)?))
+        }
+        EDWARDS25519_CLEAR_COFACTOR => {
+            let [point] = split_args(NAME, args)?;
+            encode(decode_point(point)?.mul_by_cofactor())
+        }
+        _ => unreachable!("selector was checked when pricing the operation"),
+    };
+    Ok(PrecompileOutput::new(gas_used, output))
+}
+
+/// edwards25519 multi-scalar multiplication precompile logic. It takes the input bytes sent to the
+/// precompile and the gas limit, and returns the sum of `s_i * P_i` over the `scalar | point`
+/// pairs that make up the input, encoded like the operations of [`EDWARDS25519`].
+///
+/// Gas is the cost of as many multiplications, discounted by the number of pairs as in EIP-2537.
+/// An empty input, one that is not a whole number of pairs, or any invalid scalar or point is an
+/// error.
+fn edwards25519_msm(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let k = msm_pair_count(NAME, input)?;
+    let gas_used = msm_required_gas(k, EDWARDS25519_MUL_GAS);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let (scalars, points): (Vec<_>, Vec<_>) = input
+        .chunks_exact(MSM_PAIR_LEN)
+        .map(|pair| {
+            let [scalar, point] = split_args(NAME, pair)?;
+            Ok((decode_scalar(NAME, scalar)?, decode_point(point)?))
+        })
+        .collect::<Result<_, PrecompileError>>()?;
+    let sum = EdwardsPoint::vartime_multiscalar_mul(scalars, points);
+    Ok(PrecompileOutput::new(gas_used, encode(sum)))
+}
+
+/// Decodes an edwards25519 point, failing if the encoding is not canonical or not on the curve.
+fn decode_point(bytes: &[u8; 32]) -> Result<EdwardsPoint, PrecompileError> {
+    decompress_canonical(bytes)
+        .ok_or_else(|| PrecompileError::other(format!("{NAME}: invalid point encoding")))
+}
+
+/// Returns the little-endian affine coordinates `[x, y]` of a point whose encoding has already
+/// been validated by [`decode_point`].
+fn affine_coordinates(bytes: &[u8; 32]) -> [[u8; 32]; 2] {
+    let mut y_bytes = *bytes;
+    let x_is_negative = y_bytes[31] >> 7 == 1;
+    y_bytes[31] &= 0x7f;
+    let y = field::from_bytes(&y_bytes).expect("encoding is canonical");
+
+    // x^2 = (y^2 - 1) / (d * y^2 + 1)
+    let y2 = y.square();
+    let (is_square, mut x) = field::sqrt_ratio(
+        &(y2 - FieldElement::ONE),
+        &(EDWARDS_D * y2 + FieldElement::ONE),
+    );
+    debug_assert!(is_square, "point is on the curve");
+    if x_is_negative {
+        x = -x;
+    }
+    [field::to_bytes(&x), y_bytes]
+}
+
+/// Encodes an edwards25519 point as the output of an operation.
+fn encode(point: EdwardsPoint) -> Bytes {
+    point.compress().to_bytes().to_vec().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use curve25519_dalek::{constants::ED25519_BASEPOINT_COMPRESSED, Scalar};
+    use revm::primitives::hex;
+
+    /// The affine coordinates of the basepoint, little-endian.
+    const BASEPOINT_X: [u8; 32] =
+        hex!("1ad5258f602d56c9b2a7259560c72c695cdcd6fd31e2a4c0fe536ecdd3366921");
+    const BASEPOINT_Y: [u8; 32] =
+        hex!("5866666666666666666666666666666666666666666666666666666666666666");
+
+    /// Runs an operation of [`EDWARDS25519`] on the concatenated arguments.
+    fn run(selector: u8, args: &[&[u8]]) -> Bytes {
+        let input = [&[selector][..], &args.concat()].concat().into();
+        edwards25519_run(&input, u64::MAX).unwrap().bytes
+    }
+
+    #[test]
+    fn decompresses_basepoint() {
+        let output = run(
+            EDWARDS25519_DECOMPRESS,
+            &[ED25519_BASEPOINT_COMPRESSED.as_bytes()],
+        );
+        assert_eq!(output[..], [BASEPOINT_X, BASEPOINT_Y].concat());
+    }
+
+    #[test]
+    fn decompresses_negated_basepoint() {
+        let neg = run(EDWARDS25519_NEG, &[ED25519_BASEPOINT_COMPRESSED.as_bytes()]);
+        // the negation only flips the sign bit of the encoding
+        let mut expected = *ED25519_BASEPOINT_COMPRESSED.as_bytes();
+        expected[31] |= 0x80;
+        assert_eq!(neg[..], expected);
+
+        // x = p - x_B, which is odd, and y is unchanged
+        let output = run(EDWARDS25519_DECOMPRESS, &[&neg]);
+        let x = hex!("d32ada709fd2a9364d58da6a9f38d396a3232902ce1d5b3f01ac91322cc9965e");
+        assert_eq!(output[..], [x, BASEPOINT_Y].concat());
+    }
+
+    #[test]
+    fn sqrt_ratio_detects_non_squares() {
+        // y = 2 is not the y coordinate of a point, so (y^2 - 1) / (d * y^2 + 1) has no root
+        let mut y = [0; 32];
+        y[0] = 2;
+        let y2 = field::from_bytes(&y).unwrap().square();
+        let (is_square, _) = field::sqrt_ratio(
+            &(y2 - FieldElement::ONE),
+            &(EDWARDS_D * y2 + FieldElement::ONE),
+        );
+        assert!(!is_square);
+    }
+
+    #[test]
+    fn msm_matches_repeated_mul_and_add() {
+        let pairs: Vec<[[u8; 32]; 2]> = (1..=5u64)
+            .map(|i| {
+                let scalar = Scalar::from(1_000 + i).to_bytes();
+                let point = EdwardsPoint::mul_base(&Scalar::from(i));
+                [scalar, point.compress().to_bytes()]
+            })
+            .collect();
+        let expected = pairs
+            .iter()
+            .map(|[scalar, point]| run(EDWARDS25519_MUL, &[scalar, point]))
+            .reduce(|sum, product| run(EDWARDS25519_ADD, &[&sum, &product]))
+            .unwrap();
+
+        let input = pairs.concat().concat().into();
+        let output = edwards25519_msm(&input, u64::MAX).unwrap();
+        assert_eq!(output.bytes, expected);
+        assert_eq!(output.gas_used, msm_required_gas(5, EDWARDS25519_MUL_GAS));
+        assert!(output.gas_used < 5 * EDWARDS25519_MUL_GAS);
+    }
+
+    #[test]
+    fn rejects_non_canonical_points() {
+        // y = p + 1, an encoding of the identity
+        let mut identity = [0xff; 32];
+        identity[0] = 0xee;
+        identity[31] = 0x7f;
+        let input = [&[EDWARDS25519_NEG][..], &identity].concat().into();
+        assert!(edwards25519_run(&input, u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Arithmetic in the field of integers modulo `p = 2^255 - 19`, which curve25519 is defined over.
+//!
+//! curve25519-dalek keeps its field elements private, so the few precompiles that need to work
+//! with coordinates directly use these instead.
+
+use crypto_bigint::{
+    impl_modulus,
+    modular::constant_mod::{Residue, ResidueParams},
+    Encoding, U256, U384,
+};
+
+impl_modulus!(
+    Modulus,
+    U256,
+    "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"
+);
+
+/// An element of the field of integers modulo `2^255 - 19`.
+pub(crate) type FieldElement = Residue<Modulus, { U256::LIMBS }>;
+
+/// The edwards25519 curve constant `d = -121665 / 121666`.
+pub(crate) const EDWARDS_D: FieldElement = FieldElement::new(&U256::from_be_hex(
+    "52036cee2b6ffe738cc740797779e89800700a4d4141d8ab75eb4dca135978a3",
+));
+
+/// A square root of -1.
+pub(crate) const SQRT_M1: FieldElement = FieldElement::new(&U256::from_be_hex(
+    "2b8324804fc1df0b2b4d00993dfbd7a72f431806ad2fe478c4ee1b274a0ea0b0",
+));
+
+/// The Curve25519 Montgomery curve constant `A = 486662`.
+pub(crate) const MONTGOMERY_A: FieldElement = FieldElement::new(&U256::from_u32(486_662));
+
+/// The square root of `-(A + 2) = -486664` with an even encoding, which scales the x coordinate in
+/// the rational map from Curve25519 to edwards25519.
+pub(crate) const SQRT_MINUS_A_PLUS_2: FieldElement = FieldElement::new(&U256::from_be_hex(
+    "0f26edf460a006bbd27b08dc03fc4f7ec5a1d3d14b7d1a82cc6e04aaff457e06",
+));
+
+/// The exponent `(p - 5) / 8` used to compute square roots.
+const P_MINUS_5_DIV_8: U256 =
+    U256::from_be_hex("0ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffd");
+
+/// Decodes a field element from its 32-byte little-endian encoding, returning `None` if it is not
+/// below `p`. The top bit, which edwards25519 uses for the sign of `x`, must be masked by the
+/// caller.
+pub(crate) fn from_bytes(bytes: &[u8; 32]) -> Option<FieldElement> {
+    let value = U256::from_le_bytes(*bytes);
+    (value < Modulus::MODULUS).then(|| FieldElement::new(&value))
+}
+
+/// Reduces a 48-byte big-endian integer modulo `p`, as RFC 9380 `hash_to_field` does with the
+/// output of `expand_message`.
+pub(crate) fn from_be_bytes_wide(bytes: &[u8; 48]) -> FieldElement {
+    let value = U384::from_be_bytes(*bytes).wrapping_rem(&Modulus::MODULUS.resize());
+    FieldElement::new(&value.resize())
+}
+
+/// Encodes a field element as 32 little-endian bytes.
+pub(crate) fn to_bytes(element: &FieldElement) -> [u8; 32] {
+    element.retrieve().to_le_bytes()
+}
+
+/// Returns `true` if the canonical encoding of the element is odd, which RFC 8032 and RFC 9380
+/// (as `sgn0`) both treat as the element being negative.
+pub(crate) fn is_negative(element: &FieldElement) -> bool {
+    to_bytes(element)[0] & 1 == 1
+}
+
+/// Returns the inverse of the element, or zero if it is zero, like `inv0` in RFC 9380.
+pub(crate) fn invert(element: &FieldElement) -> FieldElement {
+    let (inverse, is_invertible) = element.invert();
+    if is_invertible.into() {
+        inverse
+    } else {
+        FieldElement::ZERO
+    }
+}
+
+/// Computes the non-negative square root of `u / v`, returning it with `true` if `u / v` is a
+/// square, or `false` and an unspecified element otherwise.
+pub(crate) fn sqrt_ratio(u: &FieldElement, v: &FieldElement) -> (bool, FieldElement) {
+    let v3 = v.square() * v;
+    let v7 = v3.square() * v;
+    let mut r = u * v3 * (u * v7).pow(&P_MINUS_5_DIV_8);
+    let check = v * r.square();
+
+    let correct_sign = check == *u;
+    let flipped_sign = check == -u;
+    let flipped_sign_i = check == -(u * SQRT_M1);
+    if flipped_sign || flipped_sign_i {
+        r *= SQRT_M1;
+    }
+    if is_negative(&r) {
+        r = -r;
+    }
+    (correct_sign || flipped_sign, r)
+}
@@ -0,0 +1,367 @@
+//! # Hash-to-curve Precompile
+//!
+//! This module implements a precompile for the [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380)
+//! hash-to-curve suites over curve25519, which VRFs, OPRFs and BLS-like constructions use to hash
+//! messages to points with domain separation.
+//!
+//! The [`HASH_TO_CURVE`] const represents the implementation of this precompile, with the address
+//! that it is currently deployed at. The first byte of the input selects the suite.
+
+use crate::{
+    addresses::HASH_TO_CURVE_ADDRESS,
+    field::{self, FieldElement, MONTGOMERY_A, SQRT_MINUS_A_PLUS_2},
+};
+use crypto_bigint::U256;
+use curve25519_dalek::{edwards::CompressedEdwardsY, EdwardsPoint, RistrettoPoint};
+use revm::{
+    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult},
+};
+use sha2::{Digest, Sha512};
+
+/// Name of the precompile, used in error messages.
+const NAME: &str = "hash_to_curve";
+
+/// Selector of the `edwards25519_XMD:SHA-512_ELL2_RO_` suite.
+const EDWARDS25519_XMD_SHA512_ELL2_RO: u8 = 0x00;
+/// Selector of the `edwards25519_XMD:SHA-512_ELL2_NU_` suite.
+const EDWARDS25519_XMD_SHA512_ELL2_NU: u8 = 0x01;
+/// Selector of the `ristretto255_XMD:SHA-512_R255MAP_RO_` suite.
+const RISTRETTO255_XMD_SHA512_R255MAP_RO: u8 = 0x02;
+
+/// Base gas fee for the `edwards25519_XMD:SHA-512_ELL2_RO_` suite, which maps two field elements.
+const EDWARDS25519_XMD_SHA512_ELL2_RO_BASE: u64 = 6_000;
+/// Base gas fee for the `edwards25519_XMD:SHA-512_ELL2_NU_` suite, which maps one field element.
+const EDWARDS25519_XMD_SHA512_ELL2_NU_BASE: u64 = 2_500;
+/// Base gas fee for the `ristretto255_XMD:SHA-512_R255MAP_RO_` suite.
+const RISTRETTO255_XMD_SHA512_R255MAP_RO_BASE: u64 = 1_000;
+
+/// Gas fee charged for every 32-byte word that SHA-512 hashes, rounded up.
+const HASH_TO_CURVE_PER_WORD: u64 = 12;
+
+/// Length of the SHA-512 output, `b_in_bytes` in RFC 9380.
+const SHA512_OUTPUT_LEN: usize = 64;
+
+/// Length of the SHA-512 input block, `s_in_bytes` in RFC 9380.
+const SHA512_BLOCK_LEN: usize = 128;
+
+/// Number of bytes of `expand_message` output reduced to each field element, `L` in RFC 9380.
+const FIELD_ELEMENT_LEN: usize = 48;
+
+/// The Elligator 2 non-square `Z` that RFC 9380 uses for curve25519.
+const ELL2_Z: FieldElement = FieldElement::new(&U256::from_u8(2));
+
+/// Returns the hash-to-curve precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [HASH_TO_CURVE].into_iter()
+}
+
+/// Hash-to-curve precompile.
+pub const HASH_TO_CURVE: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(HASH_TO_CURVE_ADDRESS),
+    Precompile::Standard(hash_to_curve_run),
+);
+
+/// Hash-to-curve precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit, and hashes the message to a point with the suite selected by the first byte of the
+/// input.
+///
+/// The input is encoded as follows:
+///
+/// | suite | DST length |    DST    |  message  |
+/// | :---: | :--------: | :-------: | :-------: |
+/// |   1   |      1     | 1 to 255  | remaining |
+///
+/// The suites are:
+///
+/// | selector | suite                                  | output                   | base gas |
+/// | :------: | :------------------------------------: | :----------------------: | :------: |
+/// |   0x00   | `edwards25519_XMD:SHA-512_ELL2_RO_`    | compressed edwards25519  | 6,000    |
+/// |   0x01   | `edwards25519_XMD:SHA-512_ELL2_NU_`    | compressed edwards25519  | 2,500    |
+/// |   0x02   | `ristretto255_XMD:SHA-512_R255MAP_RO_` | ristretto255 encoding    | 1,000    |
+///
+/// On top of the base gas, [`HASH_TO_CURVE_PER_WORD`] is charged for every 32-byte word of the
+/// message, and for every word of the DST each time `expand_message_xmd` hashes it, which is once
+/// more than the number of 64-byte blocks the suite expands the message to. An empty DST, an
+/// unknown suite or a truncated input is an error.
+fn hash_to_curve_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some((&[suite, dst_len], rest)) = input.split_first_chunk::<2>() else {
+        return Err(PrecompileError::other(format!("{NAME}: input is too short")).into());
+    };
+    let dst_len = dst_len as usize;
+    if dst_len == 0 {
+        return Err(PrecompileError::other(format!("{NAME}: DST should not be empty")).into());
+    }
+    if rest.len() < dst_len {
+        return Err(PrecompileError::other(format!("{NAME}: input is too short")).into());
+    }
+    let (dst, msg) = rest.split_at(dst_len);
+
+    let (base, expand_len) = match suite {
+        EDWARDS25519_XMD_SHA512_ELL2_RO => {
+            (EDWARDS25519_XMD_SHA512_ELL2_RO_BASE, 2 * FIELD_ELEMENT_LEN)
+        }
+        EDWARDS25519_XMD_SHA512_ELL2_NU => {
+            (EDWARDS25519_XMD_SHA512_ELL2_NU_BASE, FIELD_ELEMENT_LEN)
+        }
+        RISTRETTO255_XMD_SHA512_R255MAP_RO => {
+            (RISTRETTO255_XMD_SHA512_R255MAP_RO_BASE, SHA512_OUTPUT_LEN)
+        }
+        _ => {
+            return Err(
+                PrecompileError::other(format!("{NAME}: unknown suite {suite:#04x}")).into(),
+            )
+        }
+    };
+    let dst_hashes = expand_len.div_ceil(SHA512_OUTPUT_LEN) as u64 + 1;
+    let words = words(msg.len()) + dst_hashes * words(dst.len());
+    let gas_used = base + HASH_TO_CURVE_PER_WORD * words;
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let point = match suite {
+        EDWARDS25519_XMD_SHA512_ELL2_RO => hash_to_edwards25519(msg, dst).compress().to_bytes(),
+        EDWARDS25519_XMD_SHA512_ELL2_NU => encode_to_edwards25519(msg, dst).compress().to_bytes(),
+        _ => hash_to_ristretto255(msg, dst).compress().to_bytes(),
+    };
+    Ok(PrecompileOutput::new(gas_used, point.to_vec().into()))
+}
+
+/// Returns the number of 32-byte words in `len` bytes, rounded up.
+fn words(len: usize) -> u64 {
+    len.div_ceil(32) as u64
+}
+
+/// `hash_to_curve` for the `edwards25519_XMD:SHA-512_ELL2_RO_` suite.
+fn hash_to_edwards25519(msg: &[u8], dst: &[u8]) -> EdwardsPoint {
+    let [u0, u1] = hash_to_field(msg, dst);
+    (map_to_curve(&u0) + map_to_curve(&u1)).mul_by_cofactor()
+}
+
+/// `encode_to_curve` for the `edwards25519_XMD:SHA-512_ELL2_NU_` suite. The DST must not be
+/// empty or longer than 255 bytes.
+pub(crate) fn encode_to_edwards25519(msg: &[u8], dst: &[u8]) -> EdwardsPoint {
+    let [u] = hash_to_field(msg, dst);
+    map_to_curve(&u).mul_by_cofactor()
+}
+
+/// `hash_to_ristretto255` for the `ristretto255_XMD:SHA-512_R255MAP_RO_` suite.
+fn hash_to_ristretto255(msg: &[u8], dst: &[u8]) -> RistrettoPoint {
+    let mut uniform_bytes = [0; SHA512_OUTPUT_LEN];
+    expand_message_xmd(msg, dst, &mut uniform_bytes);
+    // the one-way map of RFC 9496, which is what dalek implements
+    RistrettoPoint::from_uniform_bytes(&uniform_bytes)
+}
+
+/// Hashes a message to `N` elements of the curve25519 field.
+fn hash_to_field<const N: usize>(msg: &[u8], dst: &[u8]) -> [FieldElement; N] {
+    let mut uniform_bytes = [0; 96];
+    let uniform_bytes = &mut uniform_bytes[..N * FIELD_ELEMENT_LEN];
+    expand_message_xmd(msg, dst, uniform_bytes);
+    std::array::from_fn(|i| {
+        let bytes = &uniform_bytes[i * FIELD_ELEMENT_LEN..(i + 1) * FIELD_ELEMENT_LEN];
+        field::from_be_bytes_wide(bytes.try_into().unwrap())
+    })
+}
+
+/// `expand_message_xmd` with SHA-512, filling `out` with uniform bytes. `out` must be at most
+/// 255 SHA-512 outputs long and `dst` at most 255 bytes long, which callers guarantee.
+fn expand_message_xmd(msg: &[u8], dst: &[u8], out: &mut [u8]) {
+    let ell = out.len().div_ceil(SHA512_OUTPUT_LEN);
+    let dst_len = [dst.len() as u8];
+
+    let b_0 = Sha512::new()
+        .chain_update([0; SHA512_BLOCK_LEN])
+        .chain_update(msg)
+        .chain_update((out.len() as u16).to_be_bytes())
+        .chain_update([0])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+
+    let mut b_i = Sha512::new()
+        .chain_update(b_0)
+        .chain_update([1])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize();
+    for (i, chunk) in out.chunks_mut(SHA512_OUTPUT_LEN).enumerate() {
+        chunk.copy_from_slice(&b_i[..chunk.len()]);
+        if i + 1 < ell {
+            let mut xored = b_0;
+            xored.iter_mut().zip(b_i).for_each(|(x, b)| *x ^= b);
+            b_i = Sha512::new()
+                .chain_update(xored)
+                .chain_update([i as u8 + 2])
+                .chain_update(dst)
+                .chain_update(dst_len)
+                .finalize();
+        }
+    }
+}
+
+/// Maps a field element to edwards25519 with Elligator 2 on Curve25519 followed by the rational
+/// map to edwards25519, as RFC 9380 specifies for the `ELL2` suites. The result is not cleared of
+/// its cofactor.
+fn map_to_curve(u: &FieldElement) -> EdwardsPoint {
+    let one = FieldElement::ONE;
+
+    // Elligator 2 onto the Montgomery curve v^2 = u^3 + A * u^2 + u
+    let mut x1 = -MONTGOMERY_A * field::invert(&(one + ELL2_Z * u.square()));
+    if x1 == FieldElement::ZERO {
+        x1 = -MONTGOMERY_A;
+    }
+    let gx1 = (x1 + MONTGOMERY_A) * x1.square() + x1;
+    let x2 = -x1 - MONTGOMERY_A;
+    let gx2 = (x2 + MONTGOMERY_A) * x2.square() + x2;
+    let (s, t) = match field::sqrt_ratio(&gx1, &one) {
+        // the root has to be negative when gx1 is square
+        (true, root) => (x1, -root),
+        (false, _) => (x2, field::sqrt_ratio(&gx2, &one).1),
+    };
+
+    // (x, y) = (sqrt(-486664) * s / t, (s - 1) / (s + 1)), sharing one inversion
+    let inverse = field::invert(&(t * (s + one)));
+    if inverse == FieldElement::ZERO {
+        return EdwardsPoint::default();
+    }
+    let x = SQRT_MINUS_A_PLUS_2 * s * (s + one) * inverse;
+    let y = (s - one) * t * inverse;
+
+    let mut encoding = field::to_bytes(&y);
+    encoding[31] |= (field::is_negative(&x) as u8) << 7;
+    CompressedEdwardsY(encoding)
+        .decompress()
+        .expect("the map outputs points on the curve")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::hex;
+
+    /// Encodes the input of [`HASH_TO_CURVE`].
+    fn input(suite: u8, dst: &[u8], msg: &[u8]) -> Bytes {
+        [&[suite, dst.len() as u8], dst, msg].concat().into()
+    }
+
+    /// Compresses an affine point given as big-endian coordinates, as the RFC 9380 vectors are.
+    fn compress(x: [u8; 32], y: [u8; 32]) -> [u8; 32] {
+        let mut encoding = y;
+        encoding.reverse();
+        encoding[31] |= (x[31] & 1) << 7;
+        encoding
+    }
+
+    #[test]
+    fn expand_message_xmd_vectors() {
+        // RFC 9380 K.3
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        let mut out = [0; 0x20];
+        expand_message_xmd(b"", dst, &mut out);
+        assert_eq!(
+            out,
+            hex!("6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba")
+        );
+        let mut out = [0; 0x80];
+        expand_message_xmd(b"abc", dst, &mut out);
+        assert_eq!(
+            out,
+            hex!(
+                "7f1dddd13c08b543f2e2037b14cefb255b44c83cc397c1786d975653e36a6b11"
+                "bdd7732d8b38adb4a0edc26a0cef4bb45217135456e58fbca1703cd6032cb134"
+                "7ee720b87972d63fbf232587043ed2901bce7f22610c0419751c065922b48843"
+                "1851041310ad659e4b23520e1772ab29dcdeb2002222a363f0c2b1c972b3efe1"
+            )
+        );
+    }
+
+    #[test]
+    fn edwards25519_ro_vectors() {
+        // RFC 9380 J.5.1
+        let dst = b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_";
+        let vectors: [(&[u8], _, _); 2] = [
+            (
+                b"",
+                hex!("3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6"),
+                hex!("09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21"),
+            ),
+            (
+                b"abc",
+                hex!("608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad"),
+                hex!("1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531"),
+            ),
+        ];
+        for (msg, x, y) in vectors {
+            let output = hash_to_curve_run(&input(0x00, dst, msg), u64::MAX).unwrap();
+            assert_eq!(output.bytes.as_ref(), compress(x, y));
+        }
+    }
+
+    #[test]
+    fn edwards25519_nu_vectors() {
+        // RFC 9380 J.5.2
+        let dst = b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_NU_";
+        let vectors: [(&[u8], _, _); 2] = [
+            (
+                b"",
+                hex!("1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da"),
+                hex!("222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b"),
+            ),
+            (
+                b"abc",
+                hex!("5f13cc69c891d86927eb37bd4afc6672360007c63f68a33ab423a3aa040fd2a8"),
+                hex!("67732d50f9a26f73111dd1ed5dba225614e538599db58ba30aaea1f5c827fa42"),
+            ),
+        ];
+        for (msg, x, y) in vectors {
+            let output = hash_to_curve_run(&input(0x01, dst, msg), u64::MAX).unwrap();
+            assert_eq!(output.bytes.as_ref(), compress(x, y));
+        }
+    }
+
+    #[test]
+    fn ristretto255_ro_suite() {
+        // RFC 9380 has no vectors for this suite, which is the RFC 9496 one-way map of 64 bytes
+        // from the expander checked above
+        let dst = b"QUUX-V01-CS02-with-ristretto255_XMD:SHA-512_R255MAP_RO_";
+        let mut uniform_bytes = [0; 64];
+        expand_message_xmd(b"abc", dst, &mut uniform_bytes);
+        let output = hash_to_curve_run(&input(0x02, dst, b"abc"), u64::MAX).unwrap();
+        assert_eq!(
+            output.bytes.as_ref(),
+            RistrettoPoint::from_uniform_bytes(&uniform_bytes)
+                .compress()
+                .as_bytes()
+        );
+
+        // RFC 9496 A.3
+        let uniform_bytes = hex!(
+            "5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c1"
+            "4d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6"
+        );
+        assert_eq!(
+            RistrettoPoint::from_uniform_bytes(&uniform_bytes)
+                .compress()
+                .to_bytes(),
+            hex!("3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46")
+        );
+    }
+
+    #[test]
+    fn gas_grows_with_dst_length() {
+        // the random oracle suite expands to two blocks, so the DST is hashed three times
+        let dst = [b'D'; 255];
+        let long_dst = input(0x00, &dst, b"abc");
+        let gas = EDWARDS25519_XMD_SHA512_ELL2_RO_BASE + HASH_TO_CURVE_PER_WORD * (1 + 3 * 8);
+        assert_eq!(hash_to_curve_run(&long_dst, gas).unwrap().gas_used, gas);
+        assert_eq!(
+            hash_to_curve_run(&long_dst, gas - 1).unwrap_err(),
+            PrecompileError::OutOfGas.into()
+        );
+
+        // an empty DST is an error
+        assert!(hash_to_curve_run(&input(0x00, b"", b"abc"), u64::MAX).is_err());
+    }
+}
@@ -1,4 +1,40 @@
-//! This contains an ed25519 verification precompile.
+//! This contains signature verification precompiles for schemes that revm does not support:
+//! ed25519, ed448, sr25519, secp256r1 ECDSA and BIP-340 Schnorr over secp256k1, as well as RFC 9381
+//! ECVRF proofs over edwards25519 and Bulletproofs range proofs over ristretto255. It also exposes
+//! ristretto255 group operations and edwards25519 point arithmetic, including multi-scalar
+//! multiplication, RFC 9380 hashing to those groups, and the SHA-512 family and BLAKE2b hash
+//! functions.
+
+use revm::precompile::PrecompileWithAddress;
 
 mod addresses;
-mod ed25519;
+pub mod bip340;
+pub mod blake2b;
+pub mod bulletproofs;
+pub mod ecvrf;
+pub mod ed25519;
+pub mod ed448;
+pub mod edwards25519;
+mod field;
+pub mod hash_to_curve;
+pub mod ristretto255;
+pub mod secp256r1;
+pub mod sha512;
+pub mod sr25519;
+mod utils;
+
+/// Returns all the precompiles of this crate with their addresses.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    ed25519::precompiles()
+        .chain(ed448::precompiles())
+        .chain(secp256r1::precompiles())
+        .chain(bip340::precompiles())
+        .chain(sr25519::precompiles())
+        .chain(ristretto255::precompiles())
+        .chain(edwards25519::precompiles())
+        .chain(sha512::precompiles())
+        .chain(blake2b::precompiles())
+        .chain(hash_to_curve::precompiles())
+        .chain(ecvrf::precompiles())
+        .chain(bulletproofs::precompiles())
+}
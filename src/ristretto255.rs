@@ -0,0 +1,168 @@
+//! # ristretto255 Precompile
+//!
+//! This module implements a precompile for ristretto255 group operations, the prime-order group
+//! built on top of curve25519 that Pedersen commitments and anonymous credentials use.
+//!
+//! The [`RISTRETTO255`] const represents the implementation of this precompile, with the address
+//! that it is currently deployed at. The first byte of the input selects the operation.
+//!
+//! [`RISTRETTO255_MSM`] is a separate precompile for the large multi-scalar multiplications that
+//! verifying commitments and zero-knowledge proofs reduces to. A call with `k` pairs costs at most
+//! as much as `k` [`RISTRETTO255`] multiplications, and half as much once `k` reaches 16.
+
+use crate::{
+    addresses::{RISTRETTO255_ADDRESS, RISTRETTO255_MSM_ADDRESS},
+    utils::{decode_scalar, msm_pair_count, msm_required_gas, split_args, MSM_PAIR_LEN},
+};
+use curve25519_dalek::{
+    ristretto::CompressedRistretto, traits::VartimeMultiscalarMul, RistrettoPoint,
+};
+use revm::{
+    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult, B256},
+};
+
+/// Name of the precompile, used in error messages.
+const NAME: &str = "ristretto255";
+
+/// Selector of the decode operation, which checks that a point encoding is valid.
+const RISTRETTO255_DECODE: u8 = 0x00;
+/// Selector of the point addition operation.
+const RISTRETTO255_ADD: u8 = 0x01;
+/// Selector of the point subtraction operation.
+const RISTRETTO255_SUB: u8 = 0x02;
+/// Selector of the scalar multiplication operation.
+const RISTRETTO255_MUL: u8 = 0x03;
+/// Selector of the point equality operation.
+const RISTRETTO255_EQ: u8 = 0x04;
+
+/// Gas fee for the decode operation.
+const RISTRETTO255_DECODE_GAS: u64 = 400;
+/// Gas fee for the point addition operation.
+const RISTRETTO255_ADD_GAS: u64 = 700;
+/// Gas fee for the point subtraction operation.
+const RISTRETTO255_SUB_GAS: u64 = 700;
+/// Gas fee for the scalar multiplication operation.
+const RISTRETTO255_MUL_GAS: u64 = 2_500;
+/// Gas fee for the point equality operation.
+const RISTRETTO255_EQ_GAS: u64 = 600;
+
+/// Returns the ristretto255 precompiles with their addresses.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [RISTRETTO255, RISTRETTO255_MSM].into_iter()
+}
+
+/// ristretto255 precompile.
+pub const RISTRETTO255: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(RISTRETTO255_ADDRESS),
+    Precompile::Standard(ristretto255_run),
+);
+
+/// ristretto255 multi-scalar multiplication precompile.
+pub const RISTRETTO255_MSM: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(RISTRETTO255_MSM_ADDRESS),
+    Precompile::Standard(ristretto255_msm),
+);
+
+/// ristretto255 precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit, and runs the operation selected by the first byte of the input on the arguments that
+/// follow it.
+///
+/// Points are 32-byte canonical ristretto255 encodings and scalars are 32-byte little-endian
+/// integers below the group order. The operations are:
+///
+/// | selector | operation | arguments          | output                         | gas   |
+/// | :------: | :-------: | :----------------: | :----------------------------: | :---: |
+/// |   0x00   | decode    | point              | 32-byte word, 1 if valid       | 400   |
+/// |   0x01   | add       | point, point       | point                          | 700   |
+/// |   0x02   | sub       | point, point       | point                          | 700   |
+/// |   0x03   | mul       | scalar, point      | point                          | 2,500 |
+/// |   0x04   | eq        | point, point       | 32-byte word, 1 if equal       | 600   |
+///
+/// Apart from the decode operation, invalid encodings, unknown selectors and arguments of the
+/// wrong length are errors.
+fn ristretto255_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let Some((&selector, args)) = input.split_first() else {
+        return Err(PrecompileError::other(format!("{NAME}: missing operation selector")).into());
+    };
+    let gas_used = match selector {
+        RISTRETTO255_DECODE => RISTRETTO255_DECODE_GAS,
+        RISTRETTO255_ADD => RISTRETTO255_ADD_GAS,
+        RISTRETTO255_SUB => RISTRETTO255_SUB_GAS,
+        RISTRETTO255_MUL => RISTRETTO255_MUL_GAS,
+        RISTRETTO255_EQ => RISTRETTO255_EQ_GAS,
+        _ => {
+            return Err(PrecompileError::other(format!(
+                "{NAME}: unknown operation selector {selector:#04x}"
+            ))
+            .into())
+        }
+    };
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let output: Bytes = match selector {
+        RISTRETTO255_DECODE => {
+            let [point] = split_args(NAME, args)?;
+            let valid = CompressedRistretto(*point).decompress().is_some();
+            B256::with_last_byte(valid as u8).into()
+        }
+        RISTRETTO255_ADD => {
+            let [a, b] = split_args(NAME, args)?;
+            encode(decode_point(a)? + decode_point(b)?)
+        }
+        RISTRETTO255_SUB => {
+            let [a, b] = split_args(NAME, args)?;
+            encode(decode_point(a)? - decode_point(b)?)
+        }
+        RISTRETTO255_MUL => {
+            let [scalar, point] = split_args(NAME, args)?;
+            encode(decode_scalar(NAME, scalar)? * decode_point(point)?)
+        }
+        RISTRETTO255_EQ => {
+            let [a, b] = split_args(NAME, args)?;
+            let equal = decode_point(a)? == decode_point(b)?;
+            B256::with_last_byte(equal as u8).into()
+        }
+        _ => unreachable!("selector was checked when pricing the operation"),
+    };
+    Ok(PrecompileOutput::new(gas_used, output))
+}
+
+/// ristretto255 multi-scalar multiplication precompile logic. It takes the input bytes sent to the
+/// precompile and the gas limit, and returns the sum of `s_i * P_i` over the `scalar | point`
+/// pairs that make up the input, encoded like the operations of [`RISTRETTO255`].
+///
+/// Gas is the cost of as many multiplications, discounted by the number of pairs as in EIP-2537.
+/// An empty input, one that is not a whole number of pairs, or any invalid scalar or point is an
+/// error.
+fn ristretto255_msm(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let k = msm_pair_count(NAME, input)?;
+    let gas_used = msm_required_gas(k, RISTRETTO255_MUL_GAS);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+
+    let (scalars, points): (Vec<_>, Vec<_>) = input
+        .chunks_exact(MSM_PAIR_LEN)
+        .map(|pair| {
+            let [scalar, point] = split_args(NAME, pair)?;
+            Ok((decode_scalar(NAME, scalar)?, decode_point(point)?))
+        })
+        .collect::<Result<_, PrecompileError>>()?;
+    let sum = RistrettoPoint::vartime_multiscalar_mul(scalars, points);
+    Ok(PrecompileOutput::new(gas_used, encode(sum)))
+}
+
+/// Decodes a ristretto255 point, failing if the encoding is not valid.
+fn decode_point(bytes: &[u8; 32]) -> Result<RistrettoPoint, PrecompileError> {
+    CompressedRistretto(*bytes)
+        .decompress()
+        .ok_or_else(|| PrecompileError::other(format!("{NAME}: invalid point encoding")))
+}
+
+/// Encodes a ristretto255 point as the output of an operation.
+fn encode(point: RistrettoPoint) -> Bytes {
+    point.compress().to_bytes().to_vec().into()
+}
@@ -0,0 +1,169 @@
+//! # RIP-7212 secp256r1 Precompile
+//!
+//! This module implements the [RIP-7212](https://github.com/ethereum/RIPs/blob/master/RIPS/rip-7212.md)
+//! precompile for secp256r1 curve support.
+//!
+//! The main purpose of this precompile is to verify ECDSA signatures that use the secp256r1, or
+//! P256 elliptic curve. The [`P256VERIFY`] const represents the implementation of this
+//! precompile, with the address that it is currently deployed at.
+
+use crate::addresses::P256VERIFY_ADDRESS;
+use p256::ecdsa::{signature::hazmat::PrehashVerifier, Signature, VerifyingKey};
+use revm::{
+    precompile::{u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{
+        Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, B256,
+    },
+};
+
+/// Base gas fee for secp256r1 p256verify operation.
+const P256VERIFY_BASE: u64 = 3_450;
+
+/// Returns the secp256r1 precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [P256VERIFY].into_iter()
+}
+
+/// RIP-7212 secp256r1 precompile.
+pub const P256VERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(P256VERIFY_ADDRESS),
+    Precompile::Standard(p256_verify),
+);
+
+/// secp256r1 precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output represents the result of verifying the
+/// secp256r1 signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// | signed message hash |  r  |  s  | public key x | public key y |
+/// | :-----------------: | :-: | :-: | :----------: | :----------: |
+/// |          32         | 32  | 32  |     32       |      32      |
+///
+/// As RIP-7212 specifies, the output is a 32-byte word set to 1 if the signature is valid, and is
+/// empty otherwise.
+fn p256_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    if P256VERIFY_BASE > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = if verify_impl(input).is_some() {
+        B256::with_last_byte(1).into()
+    } else {
+        Bytes::new()
+    };
+    Ok(PrecompileOutput::new(P256VERIFY_BASE, result))
+}
+
+/// Returns `Some(())` if the signature included in the input byte slice is
+/// valid, `None` otherwise.
+fn verify_impl(input: &[u8]) -> Option<()> {
+    if input.len() != 160 {
+        return None;
+    }
+
+    // msg signed (msg is already the hash of the original message)
+    let msg = &input[..32];
+    // r, s: signature
+    let sig = &input[32..96];
+    // x, y: public key
+    let pk = &input[96..160];
+
+    // prepend 0x04 to the public key: uncompressed form
+    let mut uncompressed_pk = [0u8; 65];
+    uncompressed_pk[0] = 0x04;
+    uncompressed_pk[1..].copy_from_slice(pk);
+
+    // Can fail if r or s is zero or not below the curve order.
+    let signature = Signature::from_slice(sig).ok()?;
+    // Can fail if the input is not valid, so we have to propagate the error.
+    let public_key = VerifyingKey::from_sec1_bytes(&uncompressed_pk).ok()?;
+
+    public_key.verify_prehash(msg, &signature).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::hex;
+
+    /// SHA-256 of the message `123400` that the Wycheproof ECDSA P-256 vectors sign.
+    const MSG_HASH: [u8; 32] =
+        hex!("bb5a52f42f9c9261ed4361f59422a1e30036e7c32b270c8807a419feca605023");
+
+    /// The `r` component of the Wycheproof signature.
+    const R: [u8; 32] = hex!("2ba3a8be6b94d5ec80a6d9d1190a436effe50d85a1eee859b8cc6af9bd5c2e18");
+
+    /// The `s` component of the Wycheproof signature, which is below half the curve order.
+    const LOW_S: [u8; 32] =
+        hex!("4cd60b855d442f5b3c7b11eb6c4e0ae7525fe710fab9aa7c77a67f79e6fadd76");
+
+    /// The curve order minus [`LOW_S`], which makes an equally valid signature.
+    const HIGH_S: [u8; 32] =
+        hex!("b329f479a2bbd0a5c384ee1493b1f5186a87139cac5df4087c134b49156847db");
+
+    /// The uncompressed public key of the Wycheproof vectors, without the `0x04` tag.
+    const PUBLIC_KEY: [u8; 64] = hex!(
+        "2927b10512bae3eddcfe467828128bad2903269919f7086069c8c4df6c732838"
+        "c7787964eaac00e5921fb1498a60f4606766b3d9685001558d1a974e7341513e"
+    );
+
+    /// Returns the output of [`P256VERIFY`] for a signature and public key over [`MSG_HASH`].
+    fn verify(r: &[u8; 32], s: &[u8; 32], pk: &[u8; 64]) -> Bytes {
+        let input = [MSG_HASH.as_slice(), r, s, pk].concat().into();
+        let output = p256_verify(&input, P256VERIFY_BASE).unwrap();
+        assert_eq!(output.gas_used, P256VERIFY_BASE);
+        output.bytes
+    }
+
+    #[test]
+    fn accepts_valid_signature() {
+        assert_eq!(
+            verify(&R, &LOW_S, &PUBLIC_KEY)[..],
+            B256::with_last_byte(1)[..]
+        );
+    }
+
+    #[test]
+    fn accepts_high_s() {
+        // RIP-7212 does not require a normalized s, unlike transaction signatures
+        assert_eq!(
+            verify(&R, &HIGH_S, &PUBLIC_KEY)[..],
+            B256::with_last_byte(1)[..]
+        );
+    }
+
+    #[test]
+    fn rejects_zero_r_or_s() {
+        assert!(verify(&[0; 32], &LOW_S, &PUBLIC_KEY).is_empty());
+        assert!(verify(&R, &[0; 32], &PUBLIC_KEY).is_empty());
+    }
+
+    #[test]
+    fn rejects_point_not_on_curve() {
+        let mut pk = PUBLIC_KEY;
+        pk[63] ^= 1;
+        assert!(verify(&R, &LOW_S, &pk).is_empty());
+        // the point at infinity has no affine encoding
+        assert!(verify(&R, &LOW_S, &[0; 64]).is_empty());
+    }
+
+    #[test]
+    fn rejects_wrong_length() {
+        let input = [MSG_HASH.as_slice(), &R, &LOW_S, &PUBLIC_KEY].concat();
+        for input in [&input[..159], &[input.as_slice(), &[0]].concat()] {
+            let output = p256_verify(&input.to_vec().into(), P256VERIFY_BASE).unwrap();
+            assert!(output.bytes.is_empty());
+        }
+    }
+
+    #[test]
+    fn charges_base_gas() {
+        let input = [MSG_HASH.as_slice(), &R, &LOW_S, &PUBLIC_KEY]
+            .concat()
+            .into();
+        assert!(matches!(
+            p256_verify(&input, P256VERIFY_BASE - 1),
+            Err(PrecompileErrors::Error(PrecompileError::OutOfGas))
+        ));
+    }
+}
@@ -0,0 +1,60 @@
+//! # SHA-512 Precompiles
+//!
+//! This module implements precompiles for the SHA-512 family of hash functions, which ed25519 and
+//! the chains that use it hash messages with.
+//!
+//! The [`SHA512`], [`SHA384`] and [`SHA512_256`] consts represent the implementations of these
+//! precompiles, with the addresses that they are currently deployed at. With [`SHA512`], contracts
+//! can compute the digests that [`ED25519PHVERIFY`](crate::ed25519::ED25519PHVERIFY) checks.
+
+use crate::addresses::{SHA384_ADDRESS, SHA512_256_ADDRESS, SHA512_ADDRESS};
+use revm::{
+    precompile::{calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{Bytes, PrecompileError, PrecompileOutput, PrecompileResult},
+};
+use sha2::{Digest, Sha384, Sha512, Sha512_256};
+
+/// Base gas fee for the SHA-512 family precompiles, the same as the SHA-256 precompile.
+const SHA512_BASE: u64 = 60;
+
+/// Gas fee per 32-byte word of input, the same as the SHA-256 precompile.
+const SHA512_PER_WORD: u64 = 12;
+
+/// Returns the SHA-512 family precompiles with their addresses.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [SHA512, SHA384, SHA512_256].into_iter()
+}
+
+/// SHA-512 precompile.
+pub const SHA512: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SHA512_ADDRESS),
+    Precompile::Standard(sha512_run::<Sha512>),
+);
+
+/// SHA-384 precompile.
+pub const SHA384: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SHA384_ADDRESS),
+    Precompile::Standard(sha512_run::<Sha384>),
+);
+
+/// SHA-512/256 precompile.
+pub const SHA512_256: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SHA512_256_ADDRESS),
+    Precompile::Standard(sha512_run::<Sha512_256>),
+);
+
+/// SHA-512 family precompile logic. It takes the input bytes sent to the precompile and the gas
+/// limit, and returns the digest of the input with the hash function `D`: 64 bytes for SHA-512,
+/// 48 bytes for SHA-384 and 32 bytes for SHA-512/256.
+///
+/// Gas is charged per word of input like the SHA-256 precompile at 0x02.
+fn sha512_run<D: Digest>(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let gas_used = calc_linear_cost_u32(input.len(), SHA512_BASE, SHA512_PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileError::OutOfGas.into());
+    }
+    Ok(PrecompileOutput::new(
+        gas_used,
+        D::digest(input).to_vec().into(),
+    ))
+}
@@ -0,0 +1,75 @@
+//! # sr25519 Precompile
+//!
+//! This module implements a precompile for sr25519 support.
+//!
+//! The main purpose of this precompile is to verify Schnorrkel signatures over the ristretto255
+//! group, as used by Substrate and Polkadot accounts. The [`SR25519VERIFY`] const represents the
+//! implementation of this precompile, with the address that it is currently deployed at.
+
+use crate::addresses::SR25519VERIFY_ADDRESS;
+use revm::{
+    precompile::{calc_linear_cost_u32, u64_to_address, Precompile, PrecompileWithAddress},
+    primitives::{
+        Bytes, PrecompileError, PrecompileErrors, PrecompileOutput, PrecompileResult, B256,
+    },
+};
+use schnorrkel::{PublicKey, Signature, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+
+/// Base gas fee for sr25519verify operation.
+const SR25519VERIFY_BASE: u64 = 3_450;
+
+/// Gas fee charged for every 32-byte word of the signing context and message, rounded up.
+const SR25519VERIFY_PER_WORD: u64 = 12;
+
+/// Returns the sr25519 precompile with its address.
+pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
+    [SR25519VERIFY].into_iter()
+}
+
+/// sr25519 precompile.
+pub const SR25519VERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(SR25519VERIFY_ADDRESS),
+    Precompile::Standard(sr25519_verify),
+);
+
+/// sr25519 precompile logic. It takes the input bytes sent to the precompile
+/// and the gas limit. The output represents the result of verifying the
+/// sr25519 signature of the input.
+///
+/// The input is encoded as follows:
+///
+/// | context length | signing context | signature | public key | message  |
+/// | :------------: | :-------------: | :-------: | :--------: | :------: |
+/// |       1        |    0 to 255     |    64     |     32     | variable |
+///
+/// The signing context is the label the signer bound the signature to, `b"substrate"` for
+/// Substrate accounts. Gas is charged as [`SR25519VERIFY_BASE`] plus [`SR25519VERIFY_PER_WORD`] for
+/// every 32-byte word of the signing context and message.
+fn sr25519_verify(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+    let data_len = input
+        .len()
+        .saturating_sub(1 + SIGNATURE_LENGTH + PUBLIC_KEY_LENGTH);
+    let gas_used = calc_linear_cost_u32(data_len, SR25519VERIFY_BASE, SR25519VERIFY_PER_WORD);
+    if gas_used > gas_limit {
+        return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+    }
+    let result = verify_impl(input).is_some();
+    let out = PrecompileOutput::new(gas_used, B256::with_last_byte(result as u8).into());
+    Ok(out)
+}
+
+/// Returns `Some(())` if the signature included in the input byte slice is
+/// valid, `None` otherwise.
+fn verify_impl(input: &[u8]) -> Option<()> {
+    let (&context_len, rest) = input.split_first()?;
+    let (context, rest) = rest.split_at_checked(context_len as usize)?;
+    let (sig, rest) = rest.split_at_checked(SIGNATURE_LENGTH)?;
+    let (pk, msg) = rest.split_at_checked(PUBLIC_KEY_LENGTH)?;
+
+    // Can fail if `s` is not canonical or the signature is not marked as Schnorrkel.
+    let signature = Signature::from_bytes(sig).ok()?;
+    // Can fail if the input is not valid, so we have to propagate the error.
+    let public_key = PublicKey::from_bytes(pk).ok()?;
+
+    public_key.verify_simple(context, msg, &signature).ok()
+}
@@ -0,0 +1,101 @@
+//! Helpers shared by the precompiles that operate on curve25519 points and scalars.
+
+use curve25519_dalek::Scalar;
+use revm::primitives::{PrecompileError, PrecompileErrors};
+
+/// Splits the arguments of an operation into `N` 32-byte values, failing if there are not
+/// exactly that many. `name` prefixes the error message.
+pub(crate) fn split_args<'a, const N: usize>(
+    name: &str,
+    args: &'a [u8],
+) -> Result<[&'a [u8; 32]; N], PrecompileError> {
+    if args.len() != N * 32 {
+        return Err(PrecompileError::other(format!(
+            "{name}: expected {} bytes of arguments, got {}",
+            N * 32,
+            args.len()
+        )));
+    }
+    Ok(std::array::from_fn(|i| {
+        args[i * 32..(i + 1) * 32].try_into().unwrap()
+    }))
+}
+
+/// Decodes a little-endian scalar, failing if it is not below the group order. `name` prefixes the
+/// error message.
+pub(crate) fn decode_scalar(name: &str, bytes: &[u8; 32]) -> Result<Scalar, PrecompileError> {
+    Option::from(Scalar::from_canonical_bytes(*bytes))
+        .ok_or_else(|| PrecompileError::other(format!("{name}: non-canonical scalar")))
+}
+
+/// Length of a `scalar | point` pair in the input of a multi-scalar multiplication.
+pub(crate) const MSM_PAIR_LEN: usize = 64;
+
+/// Discounts applied to the cost of a multi-scalar multiplication, in thousandths, indexed by the
+/// number of pairs minus one. Inputs with more pairs than the table get its last discount. The
+/// values follow the cost of curve25519-dalek's variable-time algorithms, including point
+/// decoding, relative to as many separate multiplications, rounded up.
+const MSM_DISCOUNT_TABLE: [u64; 16] = [
+    1000, 800, 700, 640, 600, 575, 555, 540, 530, 520, 515, 510, 505, 503, 501, 500,
+];
+
+/// Returns the number of `scalar | point` pairs in the input of a multi-scalar multiplication,
+/// failing if it is empty or not a whole number of pairs. `name` prefixes the error message.
+pub(crate) fn msm_pair_count(name: &str, input: &[u8]) -> Result<usize, PrecompileError> {
+    if input.is_empty() || !input.len().is_multiple_of(MSM_PAIR_LEN) {
+        return Err(PrecompileError::other(format!(
+            "{name}: input length should be a non-zero multiple of {MSM_PAIR_LEN}, got {}",
+            input.len()
+        )));
+    }
+    Ok(input.len() / MSM_PAIR_LEN)
+}
+
+/// Returns the gas required by a multi-scalar multiplication of `k` pairs, the discounted cost of
+/// `k` multiplications, as in EIP-2537.
+pub(crate) fn msm_required_gas(k: usize, multiplication_cost: u64) -> u64 {
+    let discount = MSM_DISCOUNT_TABLE[k.min(MSM_DISCOUNT_TABLE.len()) - 1];
+    k as u64 * discount * multiplication_cost / 1000
+}
+
+/// Tracks the gas spent by a precompile whose cost depends on the work it ends up doing.
+pub(crate) struct GasMeter {
+    pub(crate) used: u64,
+    limit: u64,
+}
+
+impl GasMeter {
+    pub(crate) const fn new(limit: u64) -> Self {
+        Self { used: 0, limit }
+    }
+
+    /// Adds `gas` to the gas used, failing if that exceeds the limit.
+    pub(crate) fn charge(&mut self, gas: u64) -> Result<(), PrecompileErrors> {
+        self.used = self.used.saturating_add(gas);
+        if self.used > self.limit {
+            return Err(PrecompileErrors::Error(PrecompileError::OutOfGas));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn msm_gas_follows_discount_table() {
+        assert_eq!(msm_required_gas(1, 2_500), 2_500);
+        assert_eq!(msm_required_gas(2, 2_500), 4_000);
+        assert_eq!(msm_required_gas(16, 2_500), 20_000);
+        // inputs past the end of the table keep its last discount
+        assert_eq!(msm_required_gas(100, 2_500), 125_000);
+
+        // each pair is cheaper than the last, but adding a pair never lowers the total
+        for k in 1..=MSM_DISCOUNT_TABLE.len() * 2 {
+            assert!(msm_required_gas(k + 1, 2_500) > msm_required_gas(k, 2_500));
+            assert!(msm_required_gas(k, 2_500) <= k as u64 * 2_500);
+        }
+        assert!(MSM_DISCOUNT_TABLE.windows(2).all(|w| w[0] >= w[1]));
+    }
+}